@@ -0,0 +1,168 @@
+//! Runs the difficulty control mechanism against a synthetic sequence of
+//! guessing-rate changes and reports block intervals, the difficulty
+//! trajectory and convergence.
+//!
+//! Each `--epoch` is given as `GUESSING_RATE:PROVING_SECONDS:NUM_BLOCKS`, e.g.
+//! `--epoch 1e9:60:500 --epoch 1e7:60:1000` simulates a sudden hundredfold
+//! drop in guessing rate after 500 blocks.
+use clap::Parser;
+use neptune_types::difficulty_control::Difficulty;
+use neptune_types::difficulty_simulator::SimulationEpoch;
+use neptune_types::difficulty_simulator::equilibrium_difficulty;
+use neptune_types::difficulty_simulator::simulate;
+use neptune_types::network::Network;
+use neptune_types::timestamp::Timestamp;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[derive(Debug, Parser)]
+#[command(about = "Simulate the difficulty control mechanism")]
+struct Args {
+    /// Network whose target block interval and minimum block time to use.
+    #[arg(long, default_value = "main")]
+    network: Network,
+
+    /// Difficulty to start from. Defaults to the equilibrium difficulty of
+    /// the first epoch.
+    #[arg(long)]
+    initial_difficulty: Option<u32>,
+
+    /// Seed for the random number generator.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// An epoch of constant network conditions, as
+    /// `GUESSING_RATE:PROVING_SECONDS:NUM_BLOCKS`. Can be repeated.
+    #[arg(long = "epoch", value_parser = parse_epoch, required = true)]
+    epochs: Vec<SimulationEpoch>,
+
+    /// Print every block, as CSV, in addition to the per-epoch summary.
+    #[arg(long)]
+    blocks: bool,
+}
+
+fn parse_epoch(input: &str) -> Result<SimulationEpoch, String> {
+    let [guessing_rate, proving_time, num_blocks] = input.split(':').collect::<Vec<_>>()[..] else {
+        return Err(format!(
+            "expected GUESSING_RATE:PROVING_SECONDS:NUM_BLOCKS, got '{input}'"
+        ));
+    };
+
+    let guessing_rate = guessing_rate
+        .parse::<f64>()
+        .map_err(|e| format!("invalid guessing rate '{guessing_rate}': {e}"))?;
+    if !guessing_rate.is_finite() || guessing_rate <= 0.0 {
+        return Err(format!(
+            "guessing rate must be positive, got {guessing_rate}"
+        ));
+    }
+
+    let proving_time = proving_time
+        .parse::<f64>()
+        .map_err(|e| format!("invalid proving time '{proving_time}': {e}"))?;
+    if !proving_time.is_finite() || proving_time < 0.0 {
+        return Err(format!(
+            "proving time must be non-negative, got {proving_time}"
+        ));
+    }
+
+    let num_blocks = num_blocks
+        .parse::<usize>()
+        .map_err(|e| format!("invalid number of blocks '{num_blocks}': {e}"))?;
+
+    Ok(SimulationEpoch::new(
+        guessing_rate,
+        Timestamp::millis((proving_time * 1000.0) as u64),
+        num_blocks,
+    ))
+}
+
+fn seconds(timestamp: Timestamp) -> f64 {
+    timestamp.to_millis() as f64 / 1000.0
+}
+
+fn main() -> Result<(), String> {
+    let args = Args::parse();
+    let network = args.network;
+
+    let initial_difficulty = match args.initial_difficulty {
+        Some(difficulty) => Difficulty::from(difficulty),
+        None => {
+            let first = args.epochs[0];
+            equilibrium_difficulty(network, first.guessing_rate, first.proving_time).ok_or(
+                "proving time of the first epoch exceeds the target block interval; \
+                 specify --initial-difficulty",
+            )?
+        }
+    };
+
+    let report = simulate(
+        network,
+        initial_difficulty,
+        &args.epochs,
+        &mut StdRng::seed_from_u64(args.seed),
+    );
+
+    println!(
+        "network: {network}, target block interval: {}s, minimum block time: {}s",
+        seconds(network.target_block_interval()),
+        seconds(network.minimum_block_time()),
+    );
+    println!("initial difficulty: {initial_difficulty}");
+
+    if args.blocks {
+        println!("height,interval_seconds,difficulty,cumulative_proof_of_work,advance_corrections");
+        for block in &report.blocks {
+            println!(
+                "{},{},{},{},{}",
+                block.height,
+                seconds(block.interval),
+                block.difficulty,
+                block.cumulative_proof_of_work,
+                block.num_advance_corrections,
+            );
+        }
+    }
+
+    for (i, epoch_report) in report.epochs.iter().enumerate() {
+        let epoch = epoch_report.epoch;
+        println!();
+        println!(
+            "epoch {i}: guessing rate {}/s, proving time {}s, {} blocks",
+            epoch.guessing_rate,
+            seconds(epoch.proving_time),
+            epoch.num_blocks,
+        );
+        println!(
+            "  block interval: mean {}s, max {}s",
+            seconds(epoch_report.mean_block_interval),
+            seconds(epoch_report.max_block_interval),
+        );
+        println!("  final difficulty: {}", epoch_report.final_difficulty);
+        if let Some(equilibrium) =
+            equilibrium_difficulty(network, epoch.guessing_rate, epoch.proving_time)
+        {
+            println!("  equilibrium difficulty: {equilibrium}");
+        }
+        println!(
+            "  advance corrections: {}",
+            epoch_report.num_advance_corrections
+        );
+        match epoch_report.converged_after {
+            Some(num_blocks) => println!("  converged after {num_blocks} blocks"),
+            None => println!("  did not converge"),
+        }
+        println!(
+            "  cumulative proof-of-work: {} (bound: {}{})",
+            epoch_report.cumulative_proof_of_work,
+            epoch_report.max_cumulative_proof_of_work,
+            if epoch_report.respects_pow_bound() {
+                ""
+            } else {
+                ", EXCEEDED"
+            },
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,496 @@
+use std::sync::OnceLock;
+
+use get_size2::GetSize;
+use num_traits::Zero;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::EnumCount;
+use twenty_first::bfe_array;
+use twenty_first::math::b_field_element::BFieldElement;
+use twenty_first::math::bfield_codec::BFieldCodec;
+use twenty_first::prelude::Tip5;
+use twenty_first::tip5::digest::Digest;
+use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+
+use crate::address::ReceivingAddress;
+use crate::block_appendix::BlockAppendix;
+use crate::block_body::BlockBody;
+use crate::block_header::BlockHeader;
+use crate::block_header::BlockHeaderField;
+use crate::block_height::BLOCKS_PER_GENERATION;
+use crate::block_height::BlockHeight;
+use crate::block_kernel::BlockKernel;
+use crate::block_kernel::BlockKernelField;
+use crate::mast_hash::HasDiscriminant;
+use crate::mast_hash::MastHash;
+use crate::mutator_set::commit;
+use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+use crate::native_currency_amount::NativeCurrencyAmount;
+use crate::network::Network;
+use crate::pow::PowMastPaths;
+use crate::time_lock::TimeLock;
+use crate::timestamp::Timestamp;
+use crate::transaction_kernel::TransactionKernelProxy;
+use crate::triton_vm::proof::Proof;
+use crate::utxo::Coin;
+use crate::utxo::Utxo;
+
+/// The block subsidy of the first generation. Halves every
+/// [`BLOCKS_PER_GENERATION`] blocks.
+pub const INITIAL_BLOCK_SUBSIDY: NativeCurrencyAmount = NativeCurrencyAmount::coins(128);
+
+/// The size of the premine, which is paid out by the genesis block instead of
+/// a block subsidy.
+pub const PREMINE_MAX_SIZE: NativeCurrencyAmount = NativeCurrencyAmount::coins(831_488);
+
+/// Premine UTXOs cannot be spent until this long after the network launched.
+pub const PREMINE_TIME_LOCK_PERIOD: Timestamp = Timestamp::months(6);
+
+/// At least half of a coinbase must be time-locked for this long after the
+/// block's timestamp.
+pub const MINING_REWARD_TIME_LOCK_PERIOD: Timestamp = Timestamp::years(3);
+
+/// The proof of a block's validity.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub enum BlockProof {
+    /// The genesis block is valid by definition and carries no proof.
+    Genesis,
+
+    /// A placeholder for blocks that are not (yet) proven, e.g. block
+    /// proposals in tests.
+    Invalid,
+
+    SingleProof(Proof),
+}
+
+/// A block: the kernel, which holds all of the block's data, and a proof that
+/// the block is valid.
+///
+/// Blocks are immutable, since the digest is cached.
+#[derive(Clone, Debug, Serialize, Deserialize, GetSize, BFieldCodec)]
+pub struct Block {
+    /// Everything but the proof
+    kernel: BlockKernel,
+
+    proof: BlockProof,
+
+    // this is only here as an optimization for Block::hash()
+    // so that we lazily compute the hash at most once.
+    #[serde(skip)]
+    #[bfield_codec(ignore)]
+    #[get_size(ignore)]
+    digest: OnceLock<Digest>,
+}
+
+impl PartialEq for Block {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash() == other.hash()
+    }
+}
+
+impl Eq for Block {}
+
+#[derive(Debug, Copy, Clone, EnumCount)]
+pub enum BlockField {
+    Kernel,
+    Proof,
+}
+
+impl HasDiscriminant for BlockField {
+    fn discriminant(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl MastHash for Block {
+    type FieldEnum = BlockField;
+
+    fn mast_sequences(&self) -> Vec<Vec<BFieldElement>> {
+        vec![self.kernel.mast_hash().encode(), self.proof.encode()]
+    }
+}
+
+impl Block {
+    pub fn new(
+        header: BlockHeader,
+        body: BlockBody,
+        appendix: BlockAppendix,
+        proof: BlockProof,
+    ) -> Self {
+        Self {
+            kernel: BlockKernel::new(header, body, appendix),
+            proof,
+            digest: OnceLock::new(),
+        }
+    }
+
+    /// The genesis block of the given network.
+    ///
+    /// The premine distribution is consensus data that is not part of this
+    /// crate. For the block, and hence [`Self::genesis_digest`], to match the
+    /// node's, it must be supplied exactly as neptune-cash defines it for the
+    /// given network, in the same order.
+    pub fn genesis(
+        network: Network,
+        premine_distribution: &[(ReceivingAddress, NativeCurrencyAmount)],
+    ) -> Self {
+        let premine_utxos = Self::premine_utxos(network, premine_distribution);
+        let sender_randomness = Self::premine_sender_randomness(network);
+
+        let mut genesis_mutator_set = MutatorSetAccumulator::default();
+        let mut genesis_tx_outputs = vec![];
+        for ((receiving_address, _amount), utxo) in premine_distribution.iter().zip(premine_utxos) {
+            let addition_record = commit(
+                Tip5::hash(&utxo),
+                sender_randomness,
+                receiving_address.privacy_digest(),
+            );
+            genesis_mutator_set.add(&addition_record);
+            genesis_tx_outputs.push(addition_record);
+        }
+
+        let total_premine_amount = premine_distribution
+            .iter()
+            .map(|(_receiving_address, amount)| *amount)
+            .sum();
+        let genesis_txk = TransactionKernelProxy {
+            inputs: vec![],
+            outputs: genesis_tx_outputs,
+            announcements: vec![],
+            fee: NativeCurrencyAmount::coins(0),
+            coinbase: Some(total_premine_amount),
+            timestamp: network.launch_date(),
+            mutator_set_hash: MutatorSetAccumulator::default().hash(),
+            merge_bit: false,
+        }
+        .into_kernel();
+
+        let body = BlockBody::new(
+            genesis_txk,
+            genesis_mutator_set,
+            MmrAccumulator::new_from_leafs(vec![]),
+            MmrAccumulator::new_from_leafs(vec![]),
+        );
+
+        Self::new(
+            BlockHeader::genesis(network),
+            body,
+            BlockAppendix::default(),
+            BlockProof::Genesis,
+        )
+    }
+
+    /// The digest of the genesis block of the given network. See
+    /// [`Self::genesis`] for the premine distribution.
+    ///
+    /// Note that the regtest launch date, and hence its genesis digest, is
+    /// not fixed.
+    pub fn genesis_digest(
+        network: Network,
+        premine_distribution: &[(ReceivingAddress, NativeCurrencyAmount)],
+    ) -> Digest {
+        Self::genesis(network, premine_distribution).hash()
+    }
+
+    /// The UTXOs paid out by the genesis block, one per premine recipient.
+    pub fn premine_utxos(
+        network: Network,
+        premine_distribution: &[(ReceivingAddress, NativeCurrencyAmount)],
+    ) -> Vec<Utxo> {
+        let release_date = network.launch_date() + PREMINE_TIME_LOCK_PERIOD;
+        premine_distribution
+            .iter()
+            .map(|(receiving_address, amount)| {
+                let coins = vec![
+                    Coin::new_native_currency(*amount),
+                    TimeLock::until(release_date),
+                ];
+                Utxo::new(receiving_address.lock_script(), coins)
+            })
+            .collect()
+    }
+
+    /// The sender randomness of the premine UTXOs. There is no sender, so it
+    /// cannot be random.
+    pub fn premine_sender_randomness(network: Network) -> Digest {
+        Digest::new(bfe_array![u64::from(network.id()), 0, 0, 0, 0])
+    }
+
+    /// The block digest, which identifies the block.
+    pub fn hash(&self) -> Digest {
+        *self.digest.get_or_init(|| self.mast_hash())
+    }
+
+    pub fn kernel(&self) -> &BlockKernel {
+        &self.kernel
+    }
+
+    pub fn header(&self) -> &BlockHeader {
+        &self.kernel.header
+    }
+
+    pub fn body(&self) -> &BlockBody {
+        &self.kernel.body
+    }
+
+    pub fn appendix(&self) -> &BlockAppendix {
+        &self.kernel.appendix
+    }
+
+    pub fn proof(&self) -> &BlockProof {
+        &self.proof
+    }
+
+    /// Size of the block in number of [`BFieldElement`]s.
+    pub fn size(&self) -> usize {
+        self.encode().len()
+    }
+
+    /// The amount the block's coinbase pays out, which may be less than the
+    /// block subsidy.
+    pub fn coinbase_amount(&self) -> NativeCurrencyAmount {
+        self.body().transaction_kernel.coinbase.unwrap_or_default()
+    }
+
+    /// The block subsidy for a block at the given height, *i.e.*, the
+    /// maximum amount a block's coinbase may pay out, excluding fees.
+    pub fn block_subsidy(block_height: BlockHeight) -> NativeCurrencyAmount {
+        let mut reward: NativeCurrencyAmount = INITIAL_BLOCK_SUBSIDY;
+        let generation = block_height.get_generation();
+
+        for _ in 0..generation {
+            reward.div_two();
+
+            // Early return here is important bc of arbitrary block heights.
+            if reward.is_zero() {
+                return NativeCurrencyAmount::zero();
+            }
+        }
+
+        reward
+    }
+
+    /// The total amount of coins that can have been issued up to and
+    /// including the block at the given height: the premine plus the block
+    /// subsidies of all blocks after genesis.
+    ///
+    /// This is an upper bound, since miners may claim less than the subsidy.
+    /// It includes time-locked coinbase outputs.
+    pub fn issued_supply(block_height: BlockHeight) -> NativeCurrencyAmount {
+        let last_height = u64::from(block_height);
+        let mut supply = PREMINE_MAX_SIZE;
+        let mut reward = INITIAL_BLOCK_SUBSIDY;
+
+        // The genesis block pays out the premine instead of a subsidy.
+        let mut first_height = 1;
+        let mut generation_end = BLOCKS_PER_GENERATION;
+        while first_height <= last_height && !reward.is_zero() {
+            let num_blocks = (last_height + 1).min(generation_end) - first_height;
+            supply = supply + reward.scalar_mul(num_blocks.try_into().unwrap());
+
+            first_height = generation_end;
+            generation_end += BLOCKS_PER_GENERATION;
+            reward.div_two();
+        }
+
+        supply
+    }
+
+    /// The height of the first block after the given height whose subsidy is
+    /// halved.
+    pub fn next_halving_height(block_height: BlockHeight) -> BlockHeight {
+        let next_generation = block_height.get_generation() + 1;
+        BlockHeight::from(next_generation * BLOCKS_PER_GENERATION)
+    }
+
+    /// Estimate when the next halving happens, assuming blocks arrive at the
+    /// network's target block interval from the given block on.
+    pub fn estimated_next_halving_date(
+        network: Network,
+        block_height: BlockHeight,
+        block_timestamp: Timestamp,
+    ) -> Timestamp {
+        let num_blocks = Self::next_halving_height(block_height) - block_height;
+        block_timestamp + network.target_block_interval() * num_blocks.try_into().unwrap()
+    }
+
+    /// Authentication paths for all fields of the block but the PoW field.
+    pub fn pow_mast_paths(&self) -> PowMastPaths {
+        PowMastPaths {
+            pow: self
+                .header()
+                .mast_path(BlockHeaderField::Pow)
+                .try_into()
+                .unwrap(),
+            header: self
+                .kernel
+                .mast_path(BlockKernelField::Header)
+                .try_into()
+                .unwrap(),
+            kernel: self.mast_path(BlockField::Kernel).try_into().unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::util_types::mmr::mmr_trait::Mmr;
+
+    use super::*;
+    use crate::address::generation_address::GenerationReceivingAddress;
+    use crate::block_header::BlockHeaderWithBlockHashWitness;
+    use crate::block_header::HeaderToBlockHashWitness;
+    use crate::test_shared::dg;
+
+    mod nc {
+        pub use neptune_cash::api::export::Network;
+        pub use neptune_cash::protocol::consensus::block::Block;
+    }
+
+    fn nc_genesis() -> (Block, nc::Block) {
+        let nc_block = nc::Block::genesis(nc::Network::Main);
+        let block = bincode::deserialize(&bincode::serialize(&nc_block).unwrap()).unwrap();
+        (block, nc_block)
+    }
+
+    /// A block with a non-empty proof, so that the proof is accounted for in
+    /// the block hash.
+    fn block_with_random_proof() -> Block {
+        let (genesis, _) = nc_genesis();
+        let proof_size = rand::random_range(1..100);
+        let proof = Proof((0..proof_size).map(|_| rand::random()).collect());
+        Block::new(
+            *genesis.header(),
+            genesis.body().clone(),
+            genesis.appendix().clone(),
+            BlockProof::SingleProof(proof),
+        )
+    }
+
+    #[test]
+    fn block_subsidy_halves_every_generation() {
+        let last_of_generation_0 = BlockHeight::from(BLOCKS_PER_GENERATION - 1);
+        let first_of_generation_1 = BlockHeight::from(BLOCKS_PER_GENERATION);
+        assert_eq!(INITIAL_BLOCK_SUBSIDY, Block::block_subsidy(0u64.into()));
+        assert_eq!(
+            INITIAL_BLOCK_SUBSIDY,
+            Block::block_subsidy(last_of_generation_0)
+        );
+        assert_eq!(
+            NativeCurrencyAmount::coins(64),
+            Block::block_subsidy(first_of_generation_1)
+        );
+        assert_eq!(
+            NativeCurrencyAmount::zero(),
+            Block::block_subsidy(BlockHeight::from(BlockHeight::MAX))
+        );
+    }
+
+    #[test]
+    fn issued_supply_is_sum_of_premine_and_subsidies() {
+        assert_eq!(PREMINE_MAX_SIZE, Block::issued_supply(0u64.into()));
+
+        let generation_boundary = BLOCKS_PER_GENERATION;
+        for height in [1, 2, 1000, generation_boundary - 1, generation_boundary] {
+            let height = BlockHeight::from(height);
+            let previous = height.previous().unwrap();
+            assert_eq!(
+                Block::issued_supply(previous) + Block::block_subsidy(height),
+                Block::issued_supply(height)
+            );
+        }
+    }
+
+    #[test]
+    fn issued_supply_never_exceeds_max() {
+        let final_supply = Block::issued_supply(BlockHeight::from(BlockHeight::MAX));
+        assert!(final_supply <= NativeCurrencyAmount::max());
+        assert!(final_supply > NativeCurrencyAmount::coins(41_999_999));
+    }
+
+    #[test]
+    fn next_halving_height_and_date() {
+        let network = Network::Main;
+        let height = BlockHeight::from(BLOCKS_PER_GENERATION - 10);
+        assert_eq!(
+            BlockHeight::from(BLOCKS_PER_GENERATION),
+            Block::next_halving_height(height)
+        );
+        assert_eq!(
+            BlockHeight::from(2 * BLOCKS_PER_GENERATION),
+            Block::next_halving_height(BLOCKS_PER_GENERATION.into())
+        );
+
+        let now = Timestamp::now();
+        assert_eq!(
+            now + network.target_block_interval() * 10,
+            Block::estimated_next_halving_date(network, height, now)
+        );
+    }
+
+    #[test]
+    fn genesis_pays_out_premine() {
+        let network = Network::Main;
+        let premine_distribution = [1u64, 2, 3]
+            .map(|i| {
+                let seed = Digest::new(bfe_array![i, 0, 0, 0, 0]);
+                let address = GenerationReceivingAddress::derive_from_seed(seed);
+                (
+                    ReceivingAddress::from(address),
+                    NativeCurrencyAmount::coins(1000 * i as u32),
+                )
+            })
+            .to_vec();
+
+        let genesis = Block::genesis(network, &premine_distribution);
+        let kernel = &genesis.body().transaction_kernel;
+        assert_eq!(Some(NativeCurrencyAmount::coins(6000)), kernel.coinbase);
+        assert_eq!(3, kernel.outputs.len());
+        assert_eq!(3, genesis.body().mutator_set_accumulator.aocl.num_leafs());
+        assert_eq!(BlockHeight::genesis(), genesis.header().height);
+        assert_eq!(&BlockProof::Genesis, genesis.proof());
+
+        let release_date = network.launch_date() + PREMINE_TIME_LOCK_PERIOD;
+        for utxo in Block::premine_utxos(network, &premine_distribution) {
+            assert_eq!(release_date, utxo.release_date().unwrap());
+        }
+
+        assert_eq!(
+            genesis.hash(),
+            Block::genesis_digest(network, &premine_distribution)
+        );
+        assert_ne!(
+            genesis.hash(),
+            Block::genesis_digest(Network::Testnet(0), &premine_distribution)
+        );
+        assert_ne!(genesis.hash(), Block::genesis_digest(network, &[]));
+    }
+
+    #[test]
+    fn bincode_agrees_with_neptune_cash() {
+        let (block, nc_block) = nc_genesis();
+        crate::test_shared::test_bincode_serialization_for_type(block, Some(nc_block));
+    }
+
+    #[test]
+    fn hash_agrees_with_neptune_cash() {
+        let (block, nc_block) = nc_genesis();
+        assert_eq!(nc_block.hash(), dg(block.hash()));
+    }
+
+    #[test]
+    fn witness_agrees_with_block_hash() {
+        let block = block_with_random_proof();
+        let witness: HeaderToBlockHashWitness = (&block).into();
+        let calculated = BlockHeaderWithBlockHashWitness::new(*block.header(), witness).hash();
+        assert_eq!(block.hash(), calculated);
+    }
+
+    #[test]
+    fn fast_mast_hash_agrees_with_block_hash() {
+        let block = block_with_random_proof();
+        let block_pow = block.header().pow;
+        let hash_from_fast_mast = block.pow_mast_paths().fast_mast_hash(block_pow);
+        assert_eq!(block.hash(), hash_from_fast_mast);
+    }
+}
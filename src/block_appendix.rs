@@ -0,0 +1,48 @@
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use twenty_first::math::bfield_codec::BFieldCodec;
+
+use crate::triton_vm::proof::Claim;
+
+/// Contains the claims that the block proof attests to, in addition to the
+/// block's own validity.
+///
+/// Every claim in the appendix must be proven by the block proof. The claims
+/// are consensus-critical: a block whose appendix deviates from the set of
+/// claims required by the consensus rules is invalid.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default, GetSize, BFieldCodec)]
+pub struct BlockAppendix {
+    claims: Vec<Claim>,
+}
+
+impl BlockAppendix {
+    pub fn new(claims: Vec<Claim>) -> Self {
+        Self { claims }
+    }
+
+    pub fn claims(&self) -> &[Claim] {
+        &self.claims
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_imports)]
+#[allow(unused_variables)]
+#[allow(unreachable_code)]
+#[allow(non_snake_case)]
+mod generated_tests {
+    use super::*;
+    use crate::test_shared::*;
+
+    pub mod nc {
+        pub use neptune_cash::protocol::consensus::block::block_appendix::BlockAppendix;
+    }
+
+    #[test]
+    fn test_bincode_serialization_for_block_appendix() {
+        let original_instance = BlockAppendix::default();
+        let nc_instance = nc::BlockAppendix::default();
+        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+    }
+}
@@ -0,0 +1,80 @@
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::EnumCount;
+use twenty_first::math::b_field_element::BFieldElement;
+use twenty_first::math::bfield_codec::BFieldCodec;
+use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+
+use crate::mast_hash::HasDiscriminant;
+use crate::mast_hash::MastHash;
+use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+use crate::transaction_kernel::TransactionKernel;
+
+#[derive(Debug, Copy, Clone, EnumCount)]
+pub enum BlockBodyField {
+    TransactionKernel,
+    MutatorSetAccumulator,
+    LockFreeMmrAccumulator,
+    BlockMmrAccumulator,
+}
+
+impl HasDiscriminant for BlockBodyField {
+    fn discriminant(&self) -> usize {
+        *self as usize
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub struct BlockBody {
+    /// Every block contains exactly one transaction, which represents the
+    /// merger of all of the block's constituent transactions.
+    pub transaction_kernel: TransactionKernel,
+
+    /// The mutator set accumulator represents the UTXO set. It is simultaneously
+    /// an accumulator (=> compact representation and membership proofs) and an
+    /// anonymity construction (=> outputs from one transaction do not look like
+    /// inputs to another).
+    ///
+    /// This field represents the state of the mutator set *after* applying
+    /// this block.
+    pub mutator_set_accumulator: MutatorSetAccumulator,
+
+    /// Lock-free UTXOs do not come with lock scripts and do not live in the
+    /// mutator set.
+    pub lock_free_mmr_accumulator: MmrAccumulator,
+
+    /// All blocks live in an MMR, so that we can efficiently prove that a given
+    /// block is in the ancestry of a given other block. This field represents
+    /// the state of the MMR *before* this block was added.
+    pub block_mmr_accumulator: MmrAccumulator,
+}
+
+impl BlockBody {
+    pub fn new(
+        transaction_kernel: TransactionKernel,
+        mutator_set_accumulator: MutatorSetAccumulator,
+        lock_free_mmr_accumulator: MmrAccumulator,
+        block_mmr_accumulator: MmrAccumulator,
+    ) -> Self {
+        Self {
+            transaction_kernel,
+            mutator_set_accumulator,
+            lock_free_mmr_accumulator,
+            block_mmr_accumulator,
+        }
+    }
+}
+
+impl MastHash for BlockBody {
+    type FieldEnum = BlockBodyField;
+
+    fn mast_sequences(&self) -> Vec<Vec<BFieldElement>> {
+        vec![
+            self.transaction_kernel.mast_hash().encode(),
+            self.mutator_set_accumulator.encode(),
+            self.lock_free_mmr_accumulator.encode(),
+            self.block_mmr_accumulator.encode(),
+        ]
+    }
+}
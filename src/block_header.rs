@@ -6,6 +6,9 @@ use arbitrary::Arbitrary;
 */
 use get_size2::GetSize;
 use num_traits::Zero;
+use rand::Rng;
+use rand::distr::Distribution;
+use rand::distr::StandardUniform;
 use serde::Deserialize;
 use serde::Serialize;
 use strum::EnumCount;
@@ -23,11 +26,14 @@ use super::difficulty_control::ProofOfWork;
 use super::difficulty_control::difficulty_control;
 // use super::Block;
 // use crate::api::export::ReceivingAddress;
+use crate::block::Block;
 use crate::guesser_receiver_data::GuesserReceiverData;
 use crate::mast_hash::HasDiscriminant;
 use crate::mast_hash::MastHash;
 use crate::network::Network;
 use crate::pow::Pow;
+use crate::pow::PowMastPaths;
+use crate::pow::PowValidationError;
 use crate::timestamp::Timestamp;
 
 pub(crate) const BLOCK_HEADER_VERSION: BFieldElement = BFieldElement::new(0);
@@ -242,15 +248,27 @@ pub struct HeaderToBlockHashWitness {
     proof_leaf: Digest,
 }
 
-// impl From<&Block> for HeaderToBlockHashWitness {
-//     fn from(value: &Block) -> Self {
-//         Self {
-//             body_leaf: Tip5::hash_varlen(&value.body().mast_hash().encode()),
-//             appendix_leaf: Tip5::hash_varlen(&value.appendix().encode()),
-//             proof_leaf: Tip5::hash_varlen(&value.proof.encode()),
-//         }
-//     }
-// }
+// Not under test flag since it's used to build header chains in tests of
+// dependent crates.
+impl Distribution<HeaderToBlockHashWitness> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> HeaderToBlockHashWitness {
+        HeaderToBlockHashWitness {
+            body_leaf: rng.random(),
+            appendix_leaf: rng.random(),
+            proof_leaf: rng.random(),
+        }
+    }
+}
+
+impl From<&Block> for HeaderToBlockHashWitness {
+    fn from(value: &Block) -> Self {
+        Self {
+            body_leaf: Tip5::hash_varlen(&value.body().mast_hash().encode()),
+            appendix_leaf: Tip5::hash_varlen(&value.appendix().encode()),
+            proof_leaf: Tip5::hash_varlen(&value.proof().encode()),
+        }
+    }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BlockHeaderWithBlockHashWitness {
@@ -263,6 +281,10 @@ impl BlockHeaderWithBlockHashWitness {
         Self { header, witness }
     }
 
+    pub fn header(&self) -> &BlockHeader {
+        &self.header
+    }
+
     pub fn hash(&self) -> Digest {
         let block_header_leaf = Tip5::hash_varlen(&self.header.mast_hash().encode());
         let kernel_leafs = [
@@ -282,6 +304,28 @@ impl BlockHeaderWithBlockHashWitness {
     pub fn is_successor_of(&self, parent: &Self) -> bool {
         self.header.prev_block_digest == parent.hash()
     }
+
+    /// Authentication paths for all fields of the block but the PoW field.
+    pub fn pow_mast_paths(&self) -> PowMastPaths {
+        PowMastPaths {
+            pow: self
+                .header
+                .mast_path(BlockHeaderField::Pow)
+                .try_into()
+                .unwrap(),
+            header: [
+                self.witness.body_leaf,
+                Tip5::hash_pair(self.witness.appendix_leaf, Digest::default()),
+            ],
+            kernel: [self.witness.proof_leaf],
+        }
+    }
+
+    /// Check that the header carries valid proof-of-work for the given target,
+    /// which is derived from the *parent's* difficulty.
+    pub fn validate_pow(&self, target: Digest) -> Result<(), PowValidationError> {
+        self.header.pow.validate(self.pow_mast_paths(), target)
+    }
 }
 
 #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
@@ -402,6 +446,49 @@ pub(crate) mod tests {
 */   }
 }
 
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn fast_mast_hash_agrees_with_block_hash() {
+        let mut rng = rand::rng();
+        let mut header = BlockHeader::genesis(Network::Main);
+        header.pow = rng.random();
+        let witness = HeaderToBlockHashWitness {
+            body_leaf: rng.random(),
+            appendix_leaf: rng.random(),
+            proof_leaf: rng.random(),
+        };
+        let header_with_witness = BlockHeaderWithBlockHashWitness::new(header, witness);
+
+        let pow_mast_paths = header_with_witness.pow_mast_paths();
+        assert_eq!(
+            header_with_witness.hash(),
+            pow_mast_paths.fast_mast_hash(header.pow)
+        );
+    }
+
+    #[test]
+    fn random_pow_is_invalid() {
+        let mut rng = rand::rng();
+        let mut header = BlockHeader::genesis(Network::Main);
+        header.pow = rng.random();
+        let witness = HeaderToBlockHashWitness {
+            body_leaf: rng.random(),
+            appendix_leaf: rng.random(),
+            proof_leaf: rng.random(),
+        };
+        let header_with_witness = BlockHeaderWithBlockHashWitness::new(header, witness);
+
+        assert_eq!(
+            Err(PowValidationError::PathAInvalid),
+            header_with_witness.validate_pow(Difficulty::MINIMUM.target())
+        );
+    }
+}
 
 #[cfg(test)]
 #[allow(unused_imports)]
@@ -8,8 +8,8 @@ use twenty_first::tip5::digest::Digest;
 
 use super::difficulty_control::Difficulty;
 use super::difficulty_control::ProofOfWork;
+use crate::block::Block;
 use crate::block_height::BlockHeight;
-// use crate::models::blockchain::block::Block;
 use crate::native_currency_amount::NativeCurrencyAmount;
 use crate::timestamp::Timestamp;
 
@@ -69,45 +69,44 @@ impl std::fmt::Display for BlockInfo {
 }
 
 impl BlockInfo {
-    /*
-        pub fn new(
-            block: &Block,
-            genesis_digest: Digest,
-            tip_digest: Digest,
-            sibling_blocks: Vec<Digest>, // other blocks at same height
-            is_canonical: bool,
-        ) -> Self {
-            let body = block.body();
-            let header = block.header();
-            let digest = block.hash();
-            Self {
-                digest,
-                prev_block_digest: header.prev_block_digest,
-                height: header.height,
-                size: block.size(),
-                timestamp: header.timestamp,
-                difficulty: header.difficulty,
-                cumulative_proof_of_work: header.cumulative_proof_of_work,
-                num_inputs: body.transaction_kernel.inputs.len(),
-                num_outputs: body.transaction_kernel.outputs.len(),
-                num_announcements: body.transaction_kernel.announcements.len(),
-                fee: body.transaction_kernel.fee,
-                coinbase_amount: block.coinbase_amount(),
-                is_genesis: digest == genesis_digest,
-                is_tip: digest == tip_digest,
-                is_canonical,
-                sibling_blocks,
-            }
+    pub fn new(
+        block: &Block,
+        genesis_digest: Digest,
+        tip_digest: Digest,
+        sibling_blocks: Vec<Digest>, // other blocks at same height
+        is_canonical: bool,
+    ) -> Self {
+        let body = block.body();
+        let header = block.header();
+        let digest = block.hash();
+        Self {
+            digest,
+            prev_block_digest: header.prev_block_digest,
+            height: header.height,
+            size: block.size(),
+            timestamp: header.timestamp,
+            difficulty: header.difficulty,
+            cumulative_proof_of_work: header.cumulative_proof_of_work,
+            num_inputs: body.transaction_kernel.inputs.len(),
+            num_outputs: body.transaction_kernel.outputs.len(),
+            num_announcements: body.transaction_kernel.announcements.len(),
+            fee: body.transaction_kernel.fee,
+            coinbase_amount: block.coinbase_amount(),
+            is_genesis: digest == genesis_digest,
+            is_tip: digest == tip_digest,
+            is_canonical,
+            sibling_blocks,
         }
-        /// Returns expected (calculated) coinbase amount for this block's height.
-        ///
-        /// note that this calculated value may be more than the coinbase_amount
-        /// field because a miner may choose to reward themself less than the
-        /// calculated reward amount.
-        pub fn expected_coinbase_amount(&self) -> NativeCurrencyAmount {
-            Block::block_subsidy(self.height)
-        }
-    */
+    }
+
+    /// Returns expected (calculated) coinbase amount for this block's height.
+    ///
+    /// note that this calculated value may be more than the coinbase_amount
+    /// field because a miner may choose to reward themself less than the
+    /// calculated reward amount.
+    pub fn expected_coinbase_amount(&self) -> NativeCurrencyAmount {
+        Block::block_subsidy(self.height)
+    }
 }
 
 
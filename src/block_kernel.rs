@@ -0,0 +1,55 @@
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::EnumCount;
+use twenty_first::math::b_field_element::BFieldElement;
+use twenty_first::math::bfield_codec::BFieldCodec;
+
+use crate::block_appendix::BlockAppendix;
+use crate::block_body::BlockBody;
+use crate::block_header::BlockHeader;
+use crate::mast_hash::HasDiscriminant;
+use crate::mast_hash::MastHash;
+
+/// The kernel of a block contains all data that is not proof data
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub struct BlockKernel {
+    pub header: BlockHeader,
+    pub body: BlockBody,
+    pub appendix: BlockAppendix,
+}
+
+impl BlockKernel {
+    pub fn new(header: BlockHeader, body: BlockBody, appendix: BlockAppendix) -> Self {
+        Self {
+            header,
+            body,
+            appendix,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, EnumCount)]
+pub enum BlockKernelField {
+    Header,
+    Body,
+    Appendix,
+}
+
+impl HasDiscriminant for BlockKernelField {
+    fn discriminant(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl MastHash for BlockKernel {
+    type FieldEnum = BlockKernelField;
+
+    fn mast_sequences(&self) -> Vec<Vec<BFieldElement>> {
+        vec![
+            self.header.mast_hash().encode(),
+            self.body.mast_hash().encode(),
+            self.appendix.encode(),
+        ]
+    }
+}
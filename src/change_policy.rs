@@ -1,10 +1,20 @@
 use super::address::KeyType;
 use super::address::SpendingKey;
+use super::lock_script::LockScript;
+use super::native_currency_amount::NativeCurrencyAmount;
+use super::tx_input::TxInputList;
+use super::tx_output::TxOutput;
+use super::tx_output::TxOutputList;
+use super::utxo::Utxo;
 use super::utxo_notification::UtxoNotificationMedium;
+use num_traits::CheckedAdd;
+use num_traits::CheckedSub;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Debug;
 use std::sync::Arc;
+use thiserror::Error;
+use twenty_first::prelude::Digest;
 /// specifies how to handle change for a transaction.
 ///
 /// When the selected inputs represent more coins than the outputs (with fee)
@@ -81,6 +91,280 @@ impl ChangePolicy {
     pub fn burn() -> Self {
         Self::Burn
     }
+
+    /// Balance a transaction according to this policy.
+    ///
+    /// Returns the outputs, extended with a change output if the inputs
+    /// exceed the outputs plus the fee.
+    pub fn apply(
+        &self,
+        tx_inputs: &TxInputList,
+        tx_outputs: impl Into<TxOutputList>,
+        fee: NativeCurrencyAmount,
+        key_source: &mut impl ChangeKeySource,
+    ) -> Result<TxOutputList, ChangeError> {
+        let mut tx_outputs = tx_outputs.into();
+        let required = tx_outputs
+            .total_native_coins()
+            .checked_add(&fee)
+            .ok_or(ChangeError::Overflow)?;
+        let inputs = tx_inputs.total_native_coins();
+        let change = inputs
+            .checked_sub(&required)
+            .ok_or(ChangeError::InsufficientInputs { inputs, required })?;
+
+        if change.is_positive() {
+            tx_outputs.push(self.change_output(change, key_source)?);
+        }
+
+        Ok(tx_outputs)
+    }
+
+    /// Create the output that returns `change` according to this policy.
+    pub fn change_output(
+        &self,
+        change: NativeCurrencyAmount,
+        key_source: &mut impl ChangeKeySource,
+    ) -> Result<TxOutput, ChangeError> {
+        let (key, medium) = match self {
+            Self::ExactChange => return Err(ChangeError::NotExactChange(change)),
+            Self::RecoverToNextUnusedKey { key_type, medium } => {
+                (key_source.next_unused_spending_key(*key_type), *medium)
+            }
+            Self::RecoverToProvidedKey { key, medium } => (**key, *medium),
+            Self::Burn => {
+                let utxo = Utxo::new_native_currency(LockScript::burn(), change);
+                return Ok(TxOutput::no_notification_as_change(
+                    utxo,
+                    rand::random(),
+                    rand::random(),
+                ));
+            }
+        };
+
+        let address = key.to_address();
+        let sender_randomness = key_source.sender_randomness(address.privacy_digest());
+        let change_output = match medium {
+            UtxoNotificationMedium::OnChain => {
+                TxOutput::onchain_native_currency_as_change(change, sender_randomness, address)
+            }
+            UtxoNotificationMedium::OffChain => {
+                TxOutput::offchain_native_currency_as_change(change, sender_randomness, address)
+            }
+        };
+
+        Ok(change_output)
+    }
+}
+
+/// Supplies the keys and sender randomness for change outputs.
+pub trait ChangeKeySource {
+    /// The next spending key of the given type that has not been used yet.
+    ///
+    /// Implementations should mark the key as used, so that it is not handed
+    /// out again.
+    fn next_unused_spending_key(&mut self, key_type: KeyType) -> SpendingKey;
+
+    /// Sender randomness for an output to the receiver with the given privacy
+    /// digest.
+    fn sender_randomness(&self, receiver_digest: Digest) -> Digest;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ChangeError {
+    #[error("inputs {inputs} do not cover outputs plus fee {required}")]
+    InsufficientInputs {
+        inputs: NativeCurrencyAmount,
+        required: NativeCurrencyAmount,
+    },
+
+    #[error("inputs exceed outputs plus fee by {0}, but exact change is required")]
+    NotExactChange(NativeCurrencyAmount),
+
+    #[error("outputs plus fee exceed the maximum amount")]
+    Overflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_height::BlockHeight;
+    use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+    use crate::tx_input::TxInput;
+    use crate::unlocked_utxo::UnlockedUtxo;
+    use crate::wallet_entropy::WalletEntropy;
+    use num_traits::Zero;
+    use twenty_first::prelude::Tip5;
+
+    struct DevnetKeys {
+        wallet_entropy: WalletEntropy,
+        num_used: u64,
+    }
+
+    impl DevnetKeys {
+        fn new() -> Self {
+            Self {
+                wallet_entropy: WalletEntropy::devnet_wallet(),
+                num_used: 0,
+            }
+        }
+
+        fn nth_key(&self, key_type: KeyType, index: u64) -> SpendingKey {
+            match key_type {
+                KeyType::Generation => self
+                    .wallet_entropy
+                    .nth_generation_spending_key(index)
+                    .into(),
+                KeyType::Symmetric => self.wallet_entropy.nth_symmetric_key(index).into(),
+            }
+        }
+    }
+
+    impl ChangeKeySource for DevnetKeys {
+        fn next_unused_spending_key(&mut self, key_type: KeyType) -> SpendingKey {
+            self.num_used += 1;
+            self.nth_key(key_type, self.num_used - 1)
+        }
+
+        fn sender_randomness(&self, receiver_digest: Digest) -> Digest {
+            self.wallet_entropy
+                .generate_sender_randomness(BlockHeight::genesis(), receiver_digest)
+        }
+    }
+
+    fn inputs(key: SpendingKey, coins: u32) -> TxInputList {
+        let utxo = Utxo::new_native_currency(key.lock_script(), NativeCurrencyAmount::coins(coins));
+        let membership_proof = MutatorSetAccumulator::default().prove(
+            Tip5::hash(&utxo),
+            Digest::default(),
+            key.privacy_preimage(),
+        );
+        TxInput::from(UnlockedUtxo::unlock(
+            utxo,
+            key.lock_script_and_witness(),
+            membership_proof,
+        ))
+        .into()
+    }
+
+    fn payment(key_source: &DevnetKeys, coins: u32) -> TxOutput {
+        let address = key_source.nth_key(KeyType::Generation, 100).to_address();
+        TxOutput::onchain_native_currency(
+            NativeCurrencyAmount::coins(coins),
+            key_source.sender_randomness(address.privacy_digest()),
+            address,
+            false,
+        )
+    }
+
+    #[test]
+    fn change_is_recovered_with_chosen_medium() {
+        let mut key_source = DevnetKeys::new();
+        let tx_inputs = inputs(key_source.nth_key(KeyType::Generation, 0), 10);
+        let tx_outputs = TxOutputList::from([payment(&key_source, 6)]);
+        let fee = NativeCurrencyAmount::coins(1);
+
+        for medium in [
+            UtxoNotificationMedium::OnChain,
+            UtxoNotificationMedium::OffChain,
+        ] {
+            let policy = ChangePolicy::recover_to_next_unused_key(KeyType::Symmetric, medium);
+            let balanced = policy
+                .apply(&tx_inputs, tx_outputs.clone(), fee, &mut key_source)
+                .unwrap();
+
+            assert_eq!(2, balanced.len());
+            assert_eq!(NativeCurrencyAmount::coins(3), balanced.change_amount());
+            let change = balanced.change_iter().next().unwrap();
+            assert!(change.is_owned());
+            assert_eq!(
+                medium == UtxoNotificationMedium::OffChain,
+                change.is_offchain()
+            );
+        }
+        assert_eq!(2, key_source.num_used);
+
+        let provided_key = Arc::new(key_source.nth_key(KeyType::Generation, 7));
+        let policy = ChangePolicy::recover_to_provided_key(
+            provided_key.clone(),
+            UtxoNotificationMedium::OnChain,
+        );
+        let balanced = policy
+            .apply(&tx_inputs, tx_outputs, fee, &mut key_source)
+            .unwrap();
+        let change = balanced.change_iter().next().unwrap();
+        assert_eq!(
+            provided_key.lock_script_hash(),
+            change.utxo().lock_script_hash()
+        );
+        assert_eq!(2, key_source.num_used);
+    }
+
+    #[test]
+    fn exact_change_rejects_excess_inputs() {
+        let mut key_source = DevnetKeys::new();
+        let tx_inputs = inputs(key_source.nth_key(KeyType::Generation, 0), 10);
+        let policy = ChangePolicy::exact_change();
+
+        let exact = policy
+            .apply(
+                &tx_inputs,
+                [payment(&key_source, 9)],
+                NativeCurrencyAmount::coins(1),
+                &mut key_source,
+            )
+            .unwrap();
+        assert!(!exact.has_change_output());
+
+        assert_eq!(
+            ChangeError::NotExactChange(NativeCurrencyAmount::coins(2)),
+            policy
+                .apply(
+                    &tx_inputs,
+                    [payment(&key_source, 7)],
+                    NativeCurrencyAmount::coins(1),
+                    &mut key_source,
+                )
+                .unwrap_err()
+        );
+
+        assert_eq!(
+            ChangeError::InsufficientInputs {
+                inputs: NativeCurrencyAmount::coins(10),
+                required: NativeCurrencyAmount::coins(11),
+            },
+            policy
+                .apply(
+                    &tx_inputs,
+                    [payment(&key_source, 11)],
+                    NativeCurrencyAmount::zero(),
+                    &mut key_source,
+                )
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn burned_change_is_unspendable() {
+        let mut key_source = DevnetKeys::new();
+        let tx_inputs = inputs(key_source.nth_key(KeyType::Generation, 0), 10);
+        let balanced = ChangePolicy::burn()
+            .apply(
+                &tx_inputs,
+                TxOutputList::default(),
+                NativeCurrencyAmount::coins(4),
+                &mut key_source,
+            )
+            .unwrap();
+
+        let change = balanced.change_iter().next().unwrap();
+        assert_eq!(
+            NativeCurrencyAmount::coins(6),
+            change.native_currency_amount()
+        );
+        assert_eq!(LockScript::burn().hash(), change.utxo().lock_script_hash());
+        assert_eq!(0, key_source.num_used);
+    }
 }
 #[cfg(test)]
 #[allow(unused_imports)]
@@ -0,0 +1,408 @@
+//! Simulates the difficulty control mechanism over a synthetic sequence of
+//! network conditions, so as to study how the P-controller and the advance
+//! difficulty correction react to changes in guessing rate.
+//!
+//! The simulation is divided into epochs, during each of which the network's
+//! guessing rate (in guesses per second) and the time it takes to produce a
+//! block proof are constant. Blocks are found as follows:
+//!
+//!  1. guessing starts once the block proof is ready;
+//!  2. every guess succeeds with probability 1/difficulty, where the difficulty
+//!     is that of the predecessor, reduced by the advance difficulty correction
+//!     for every [`ADVANCE_DIFFICULTY_CORRECTION_WAIT`] target block intervals
+//!     that have passed since the predecessor;
+//!  3. blocks cannot come sooner than the network's minimum block time after
+//!     their predecessor.
+//!
+//! The new block's difficulty is then set by [`difficulty_control`].
+use num_bigint::BigUint;
+use num_traits::FromPrimitive;
+use num_traits::ToPrimitive;
+use num_traits::Zero;
+use rand::Rng;
+
+use crate::block_height::BlockHeight;
+use crate::difficulty_control::ADVANCE_DIFFICULTY_CORRECTION_FACTOR;
+use crate::difficulty_control::ADVANCE_DIFFICULTY_CORRECTION_WAIT;
+use crate::difficulty_control::Difficulty;
+use crate::difficulty_control::ProofOfWork;
+use crate::difficulty_control::difficulty_control;
+use crate::difficulty_control::max_cumulative_pow_after;
+use crate::mining_analytics::BlockSample;
+use crate::network::Network;
+use crate::timestamp::Timestamp;
+
+/// Number of consecutive blocks whose average interval is compared against
+/// the target block interval to decide whether the difficulty has converged.
+pub const CONVERGENCE_WINDOW: usize = 64;
+
+/// Maximum relative deviation from the target block interval that the average
+/// block interval over [`CONVERGENCE_WINDOW`] blocks may have for the
+/// difficulty to count as converged.
+pub const CONVERGENCE_TOLERANCE: f64 = 0.25;
+
+/// A period of constant network conditions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationEpoch {
+    /// Guesses per second, summed over all guessers.
+    pub guessing_rate: f64,
+
+    /// Time between a block and the moment guessing on its successor starts.
+    pub proving_time: Timestamp,
+
+    /// Number of blocks to find during this epoch.
+    pub num_blocks: usize,
+}
+
+impl SimulationEpoch {
+    pub fn new(guessing_rate: f64, proving_time: Timestamp, num_blocks: usize) -> Self {
+        Self {
+            guessing_rate,
+            proving_time,
+            num_blocks,
+        }
+    }
+}
+
+/// A block found during the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulatedBlock {
+    pub height: BlockHeight,
+    pub timestamp: Timestamp,
+
+    /// Time since the predecessor.
+    pub interval: Timestamp,
+
+    /// The difficulty set for this block's successor, as determined by
+    /// [`difficulty_control`].
+    pub difficulty: Difficulty,
+    pub cumulative_proof_of_work: ProofOfWork,
+
+    /// Number of times the advance difficulty correction reduced the
+    /// predecessor's difficulty before this block was found.
+    pub num_advance_corrections: usize,
+}
+
+impl BlockSample for SimulatedBlock {
+    fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn cumulative_proof_of_work(&self) -> ProofOfWork {
+        self.cumulative_proof_of_work
+    }
+}
+
+/// Summary of the blocks found during one epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochReport {
+    pub epoch: SimulationEpoch,
+    pub mean_block_interval: Timestamp,
+    pub max_block_interval: Timestamp,
+
+    /// The difficulty after the last block of the epoch.
+    pub final_difficulty: Difficulty,
+
+    /// Total number of advance difficulty corrections during the epoch.
+    pub num_advance_corrections: usize,
+
+    /// The number of blocks after which the average block interval over the
+    /// preceding [`CONVERGENCE_WINDOW`] blocks of the epoch came within
+    /// [`CONVERGENCE_TOLERANCE`] of the target block interval, if it did.
+    pub converged_after: Option<usize>,
+
+    /// Cumulative proof-of-work after the last block of the epoch.
+    pub cumulative_proof_of_work: ProofOfWork,
+
+    /// The bound [`max_cumulative_pow_after`] gives for the cumulative
+    /// proof-of-work at the end of the epoch, given the start of the epoch.
+    pub max_cumulative_proof_of_work: ProofOfWork,
+}
+
+impl EpochReport {
+    pub fn respects_pow_bound(&self) -> bool {
+        self.cumulative_proof_of_work <= self.max_cumulative_proof_of_work
+    }
+}
+
+/// Outcome of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub network: Network,
+    pub initial_difficulty: Difficulty,
+
+    /// All blocks found, in order, excluding the starting block.
+    pub blocks: Vec<SimulatedBlock>,
+    pub epochs: Vec<EpochReport>,
+}
+
+impl SimulationReport {
+    /// The blocks found during the epoch with the given index.
+    pub fn epoch_blocks(&self, epoch_index: usize) -> &[SimulatedBlock] {
+        let start = self.epochs[..epoch_index]
+            .iter()
+            .map(|report| report.epoch.num_blocks)
+            .sum::<usize>();
+        &self.blocks[start..start + self.epochs[epoch_index].epoch.num_blocks]
+    }
+}
+
+/// The difficulty at which, with the given guessing rate and proving time,
+/// blocks come exactly one target block interval apart on average.
+///
+/// Returns `None` if proving alone takes at least the target block interval.
+pub fn equilibrium_difficulty(
+    network: Network,
+    guessing_rate: f64,
+    proving_time: Timestamp,
+) -> Option<Difficulty> {
+    let target_block_interval = network.target_block_interval();
+    if proving_time >= target_block_interval {
+        return None;
+    }
+
+    let guessing_time = (target_block_interval - proving_time)
+        .as_duration()
+        .as_secs_f64();
+    let num_guesses = BigUint::from_f64(guessing_rate * guessing_time)?;
+    let mut limbs = num_guesses.to_u32_digits();
+    if limbs.len() > Difficulty::NUM_LIMBS {
+        return Some(Difficulty::MAXIMUM);
+    }
+
+    limbs.resize(Difficulty::NUM_LIMBS, 0);
+    Some(Difficulty::new(limbs.try_into().unwrap()))
+}
+
+/// Run the simulation, starting from a block at height zero with the given
+/// difficulty.
+///
+/// # Panics
+///
+/// Panics if the guessing rate of any epoch is not positive and finite.
+pub fn simulate<R: Rng>(
+    network: Network,
+    initial_difficulty: Difficulty,
+    epochs: &[SimulationEpoch],
+    rng: &mut R,
+) -> SimulationReport {
+    let target_block_interval = network.target_block_interval();
+    let minimum_block_time = network.minimum_block_time();
+
+    let mut height = BlockHeight::genesis();
+    let mut timestamp = network.launch_date();
+    let mut difficulty = initial_difficulty;
+    let mut cumulative_proof_of_work = ProofOfWork::zero();
+
+    let mut blocks = vec![];
+    let mut epoch_reports = vec![];
+    for &epoch in epochs {
+        assert!(
+            epoch.guessing_rate.is_finite() && epoch.guessing_rate > 0.0,
+            "guessing rate must be positive and finite, got {}",
+            epoch.guessing_rate
+        );
+
+        let max_cumulative_proof_of_work = max_cumulative_pow_after(
+            cumulative_proof_of_work,
+            difficulty,
+            epoch.num_blocks,
+            target_block_interval,
+            minimum_block_time,
+        );
+
+        let epoch_start = blocks.len();
+        for _ in 0..epoch.num_blocks {
+            let (interval, num_advance_corrections) =
+                sample_block_interval(network, difficulty, epoch, rng);
+            let new_timestamp = timestamp + interval;
+            cumulative_proof_of_work = cumulative_proof_of_work + difficulty;
+            difficulty = difficulty_control(
+                new_timestamp,
+                timestamp,
+                difficulty,
+                target_block_interval,
+                height,
+            );
+            height = height.next();
+            timestamp = new_timestamp;
+
+            blocks.push(SimulatedBlock {
+                height,
+                timestamp,
+                interval,
+                difficulty,
+                cumulative_proof_of_work,
+                num_advance_corrections,
+            });
+        }
+
+        let epoch_blocks = &blocks[epoch_start..];
+        epoch_reports.push(EpochReport {
+            epoch,
+            mean_block_interval: mean_interval(epoch_blocks),
+            max_block_interval: epoch_blocks
+                .iter()
+                .map(|block| block.interval)
+                .max()
+                .unwrap_or_else(Timestamp::zero),
+            final_difficulty: difficulty,
+            num_advance_corrections: epoch_blocks
+                .iter()
+                .map(|block| block.num_advance_corrections)
+                .sum(),
+            converged_after: converged_after(epoch_blocks, target_block_interval),
+            cumulative_proof_of_work,
+            max_cumulative_proof_of_work,
+        });
+    }
+
+    SimulationReport {
+        network,
+        initial_difficulty,
+        blocks,
+        epochs: epoch_reports,
+    }
+}
+
+/// Sample the time it takes to find the successor of a block with the given
+/// difficulty, along with the number of advance difficulty corrections that
+/// applied to it.
+///
+/// Guessing is memoryless and the effective difficulty is constant between
+/// advance corrections, so within each correction window the time until
+/// success is exponentially distributed.
+fn sample_block_interval<R: Rng>(
+    network: Network,
+    difficulty: Difficulty,
+    epoch: SimulationEpoch,
+    rng: &mut R,
+) -> (Timestamp, usize) {
+    let target_block_interval = network.target_block_interval().to_millis() as f64 / 1000.0;
+    let window_duration = target_block_interval * ADVANCE_DIFFICULTY_CORRECTION_WAIT as f64;
+    let shift_per_correction = ADVANCE_DIFFICULTY_CORRECTION_FACTOR.ilog2() as usize;
+    let proving_time = epoch.proving_time.to_millis() as f64 / 1000.0;
+
+    let mut num_corrections = (proving_time / window_duration) as usize;
+    let mut now = proving_time;
+    let block_time = loop {
+        let window_end = (num_corrections + 1) as f64 * window_duration;
+        let effective_difficulty = difficulty >> (shift_per_correction * num_corrections);
+        let success_rate =
+            epoch.guessing_rate / BigUint::from(effective_difficulty).to_f64().unwrap();
+
+        // inverse transform sampling of the exponential distribution
+        let uniform: f64 = rng.random();
+        let time_to_success = -(-uniform).ln_1p() / success_rate;
+        if now + time_to_success < window_end {
+            break now + time_to_success;
+        }
+
+        now = window_end;
+        num_corrections += 1;
+    };
+
+    let interval =
+        Timestamp::millis((block_time * 1000.0) as u64).max(network.minimum_block_time());
+    (interval, num_corrections)
+}
+
+fn mean_interval(blocks: &[SimulatedBlock]) -> Timestamp {
+    if blocks.is_empty() {
+        return Timestamp::zero();
+    }
+
+    let total = blocks
+        .iter()
+        .map(|block| block.interval.to_millis())
+        .sum::<u64>();
+    Timestamp::millis(total / blocks.len() as u64)
+}
+
+fn converged_after(blocks: &[SimulatedBlock], target_block_interval: Timestamp) -> Option<usize> {
+    let target = target_block_interval.to_millis() as f64;
+    blocks
+        .windows(CONVERGENCE_WINDOW)
+        .position(|window| {
+            let mean = mean_interval(window).to_millis() as f64;
+            (mean - target).abs() <= CONVERGENCE_TOLERANCE * target
+        })
+        .map(|position| position + CONVERGENCE_WINDOW)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn constant_guessing_rate_tracks_target() {
+        let network = Network::Main;
+        let epoch = SimulationEpoch::new(1_000_000.0, Timestamp::minutes(1), 2_000);
+        let start =
+            equilibrium_difficulty(network, epoch.guessing_rate, epoch.proving_time).unwrap();
+        let report = simulate(network, start, &[epoch], &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(2_000, report.blocks.len());
+        let [epoch_report] = report.epochs.as_slice() else {
+            panic!("expected one epoch report");
+        };
+        assert!(epoch_report.respects_pow_bound());
+        assert!(epoch_report.converged_after.is_some());
+
+        let target = network.target_block_interval().to_millis() as f64;
+        let mean = epoch_report.mean_block_interval.to_millis() as f64;
+        assert!((mean - target).abs() < 0.15 * target, "mean: {mean}");
+    }
+
+    #[test]
+    fn sudden_drop_in_guessing_rate_triggers_advance_correction() {
+        let network = Network::Main;
+        let proving_time = Timestamp::minutes(1);
+        let before = SimulationEpoch::new(1e12, proving_time, 100);
+        let after = SimulationEpoch::new(1e6, proving_time, 500);
+        let start = equilibrium_difficulty(network, before.guessing_rate, proving_time).unwrap();
+        let report = simulate(
+            network,
+            start,
+            &[before, after],
+            &mut StdRng::seed_from_u64(1),
+        );
+
+        let after_report = &report.epochs[1];
+        assert!(after_report.respects_pow_bound());
+        assert!(after_report.num_advance_corrections > 0);
+        assert!(after_report.converged_after.is_some());
+        assert!(after_report.final_difficulty < report.epochs[0].final_difficulty);
+
+        let first_block = report.epoch_blocks(1)[0];
+        assert!(first_block.num_advance_corrections > 0);
+        assert!(first_block.interval > network.target_block_interval() * 128);
+    }
+
+    #[test]
+    fn block_intervals_respect_minimum_block_time() {
+        for network in [Network::Main, Network::TestnetMock, Network::RegTest] {
+            let slow = SimulationEpoch::new(1_000.0, Timestamp::zero(), 50);
+            let fast = SimulationEpoch::new(1e9, Timestamp::zero(), 200);
+            let start =
+                equilibrium_difficulty(network, slow.guessing_rate, Timestamp::zero()).unwrap();
+            let report = simulate(network, start, &[slow, fast], &mut StdRng::seed_from_u64(2));
+
+            assert!(
+                report
+                    .blocks
+                    .iter()
+                    .all(|block| block.interval >= network.minimum_block_time())
+            );
+            assert!(report.epochs.iter().all(EpochReport::respects_pow_bound));
+            assert!(report.epochs[1].final_difficulty > report.epochs[0].final_difficulty);
+        }
+    }
+}
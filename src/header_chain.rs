@@ -0,0 +1,395 @@
+use thiserror::Error;
+use twenty_first::prelude::Digest;
+
+use crate::block_header::BlockHeader;
+use crate::block_header::BlockHeaderWithBlockHashWitness;
+use crate::difficulty_control::Difficulty;
+use crate::difficulty_control::difficulty_control;
+use crate::network::Network;
+use crate::pow::PowValidationError;
+use crate::timestamp::Timestamp;
+
+/// The ways in which a header can fail to be a valid successor of its parent.
+///
+/// Every variant carries the index, into the list of headers that was
+/// validated, of the offending child header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum HeaderChainError {
+    #[error("header {0} does not point to the digest of its predecessor")]
+    PrevBlockDigestMismatch(usize),
+
+    #[error("header {0} does not have its predecessor's height plus one")]
+    BlockHeight(usize),
+
+    #[error("header {0} violates the minimum block time")]
+    MinimumBlockTime(usize),
+
+    #[error("header {0} has wrong difficulty")]
+    Difficulty(usize),
+
+    #[error("header {0} has wrong cumulative proof-of-work")]
+    CumulativeProofOfWork(usize),
+
+    #[error("header {0} has invalid proof-of-work")]
+    ProofOfWork(usize, #[source] PowValidationError),
+}
+
+/// Return true iff the difficulty must be reset to the genesis difficulty
+/// because the time between the two blocks exceeds the network's reset
+/// interval. Only applies to networks that have such an interval.
+pub fn should_reset_difficulty(
+    network: Network,
+    current_block_timestamp: Timestamp,
+    previous_block_timestamp: Timestamp,
+) -> bool {
+    let Some(reset_interval) = network.difficulty_reset_interval() else {
+        return false;
+    };
+
+    current_block_timestamp - previous_block_timestamp > reset_interval
+}
+
+/// The difficulty a child of the given header must have.
+///
+/// Assumes the child's timestamp respects the minimum block time.
+pub fn expected_difficulty(
+    network: Network,
+    parent: &BlockHeader,
+    child_timestamp: Timestamp,
+) -> Difficulty {
+    if should_reset_difficulty(network, child_timestamp, parent.timestamp) {
+        return network.genesis_difficulty();
+    }
+
+    difficulty_control(
+        child_timestamp,
+        parent.timestamp,
+        parent.difficulty,
+        network.target_block_interval(),
+        parent.height,
+    )
+}
+
+/// Check that `child` is a valid successor of `parent`, including that the
+/// child carries valid proof-of-work for the target derived from the
+/// parent's difficulty.
+///
+/// The `index` is only used to populate the error.
+pub fn validate_successor(
+    network: Network,
+    parent: &BlockHeaderWithBlockHashWitness,
+    child: &BlockHeaderWithBlockHashWitness,
+    index: usize,
+) -> Result<(), HeaderChainError> {
+    validate_successor_with(network, parent, child, index, |child, target| {
+        child.validate_pow(target)
+    })
+}
+
+/// Validate a list of headers, ordered by ascending height, where every
+/// header must be a valid successor of the one before it, as defined by
+/// [`validate_successor`].
+///
+/// The first header is trusted, including its proof-of-work; it is the
+/// caller's responsibility to anchor it, for instance to the genesis header
+/// or to a previously validated header.
+pub fn validate_header_chain(
+    network: Network,
+    headers: &[BlockHeaderWithBlockHashWitness],
+) -> Result<(), HeaderChainError> {
+    validate_header_chain_with(network, headers, |child, target| child.validate_pow(target))
+}
+
+/// Like [`validate_successor`], but with the proof-of-work check supplied by
+/// the caller. The check receives the child and the target derived from the
+/// parent's difficulty, and runs after all other checks pass.
+fn validate_successor_with(
+    network: Network,
+    parent: &BlockHeaderWithBlockHashWitness,
+    child: &BlockHeaderWithBlockHashWitness,
+    index: usize,
+    validate_pow: impl Fn(&BlockHeaderWithBlockHashWitness, Digest) -> Result<(), PowValidationError>,
+) -> Result<(), HeaderChainError> {
+    if !child.is_successor_of(parent) {
+        return Err(HeaderChainError::PrevBlockDigestMismatch(index));
+    }
+
+    let parent_header = parent.header();
+    let child_header = child.header();
+    if child_header.height != parent_header.height.next() {
+        return Err(HeaderChainError::BlockHeight(index));
+    }
+
+    // Checked before anything subtracts timestamps, as subtraction wraps.
+    if child_header.timestamp < parent_header.timestamp + network.minimum_block_time() {
+        return Err(HeaderChainError::MinimumBlockTime(index));
+    }
+
+    if child_header.difficulty
+        != expected_difficulty(network, parent_header, child_header.timestamp)
+    {
+        return Err(HeaderChainError::Difficulty(index));
+    }
+
+    if child_header.cumulative_proof_of_work
+        != parent_header.cumulative_proof_of_work + parent_header.difficulty
+    {
+        return Err(HeaderChainError::CumulativeProofOfWork(index));
+    }
+
+    validate_pow(child, parent_header.difficulty.target())
+        .map_err(|err| HeaderChainError::ProofOfWork(index, err))
+}
+
+fn validate_header_chain_with(
+    network: Network,
+    headers: &[BlockHeaderWithBlockHashWitness],
+    validate_pow: impl Fn(&BlockHeaderWithBlockHashWitness, Digest) -> Result<(), PowValidationError>,
+) -> Result<(), HeaderChainError> {
+    for (index, (parent, child)) in headers.iter().zip(headers.iter().skip(1)).enumerate() {
+        validate_successor_with(network, parent, child, index + 1, &validate_pow)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use rand::Rng;
+
+    use super::*;
+    use crate::block_header::HeaderToBlockHashWitness;
+
+    /// Real proof-of-work requires the full guesser buffer, so all checks
+    /// but the PoW check are tested with the latter disabled.
+    fn validate_ignoring_pow(
+        network: Network,
+        headers: &[BlockHeaderWithBlockHashWitness],
+    ) -> Result<(), HeaderChainError> {
+        validate_header_chain_with(network, headers, |_, _| Ok(()))
+    }
+
+    fn child_of(
+        network: Network,
+        parent: &BlockHeaderWithBlockHashWitness,
+        timestamp: Timestamp,
+    ) -> BlockHeaderWithBlockHashWitness {
+        let mut header = BlockHeader::template_header(
+            parent.header(),
+            parent.hash(),
+            timestamp,
+            network.target_block_interval(),
+        );
+        header.difficulty = expected_difficulty(network, parent.header(), timestamp);
+        let witness: HeaderToBlockHashWitness = rand::random();
+        BlockHeaderWithBlockHashWitness::new(header, witness)
+    }
+
+    fn valid_chain(network: Network, length: usize) -> Vec<BlockHeaderWithBlockHashWitness> {
+        let mut rng = rand::rng();
+        let genesis =
+            BlockHeaderWithBlockHashWitness::new(BlockHeader::genesis(network), rng.random());
+        let mut headers = vec![genesis];
+        for _ in 1..length {
+            let parent = headers.last().unwrap();
+            let block_time = Timestamp::millis(rng.random_range(60_000..1_200_000));
+            let timestamp = parent.header().timestamp + block_time;
+            headers.push(child_of(network, parent, timestamp));
+        }
+
+        headers
+    }
+
+    /// Replace the header at the given index and rebuild all its descendants
+    /// so that only the replaced header is invalid.
+    fn replace_and_relink(
+        network: Network,
+        headers: &mut [BlockHeaderWithBlockHashWitness],
+        index: usize,
+        new_header: BlockHeader,
+    ) {
+        headers[index] = BlockHeaderWithBlockHashWitness::new(new_header, rand::random());
+        for i in index + 1..headers.len() {
+            let timestamp = headers[i].header().timestamp;
+            headers[i] = child_of(network, &headers[i - 1], timestamp);
+        }
+    }
+
+    #[test]
+    fn valid_chains_are_valid() {
+        for network in [Network::Main, Network::Testnet(0), Network::RegTest] {
+            assert_eq!(
+                Ok(()),
+                validate_ignoring_pow(network, &valid_chain(network, 20))
+            );
+        }
+        assert_eq!(Ok(()), validate_ignoring_pow(Network::Main, &[]));
+        assert_eq!(
+            Ok(()),
+            validate_ignoring_pow(Network::Main, &valid_chain(Network::Main, 1))
+        );
+    }
+
+    #[test]
+    fn reject_invalid_pow() {
+        let network = Network::Main;
+        let headers = valid_chain(network, 3);
+        assert!(matches!(
+            validate_header_chain(network, &headers),
+            Err(HeaderChainError::ProofOfWork(1, _))
+        ));
+        assert!(matches!(
+            validate_successor(network, &headers[1], &headers[2], 2),
+            Err(HeaderChainError::ProofOfWork(2, _))
+        ));
+
+        // A chain of one header has no successors to check.
+        assert_eq!(Ok(()), validate_header_chain(network, &headers[..1]));
+    }
+
+    #[test]
+    fn pow_is_checked_against_parent_difficulty() {
+        let network = Network::Testnet(0);
+        let headers = valid_chain(network, 10);
+        let checked = RefCell::new(vec![]);
+        let result = validate_header_chain_with(network, &headers, |child, target| {
+            checked.borrow_mut().push((child.hash(), target));
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
+
+        let expected = headers
+            .iter()
+            .zip(headers.iter().skip(1))
+            .map(|(parent, child)| (child.hash(), parent.header().difficulty.target()))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, checked.into_inner());
+    }
+
+    #[test]
+    fn pow_error_is_reported_with_index() {
+        let network = Network::Main;
+        let headers = valid_chain(network, 10);
+        let bad_child = headers[6].hash();
+        let result = validate_header_chain_with(network, &headers, |child, _| {
+            if child.hash() == bad_child {
+                Err(PowValidationError::ThresholdNotMet)
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(
+            Err(HeaderChainError::ProofOfWork(
+                6,
+                PowValidationError::ThresholdNotMet
+            )),
+            result
+        );
+    }
+
+    #[test]
+    fn reject_bad_prev_block_digest() {
+        let network = Network::Main;
+        let mut headers = valid_chain(network, 10);
+        let mut header = *headers[4].header();
+        header.prev_block_digest = rand::random::<Digest>();
+        replace_and_relink(network, &mut headers, 4, header);
+        assert_eq!(
+            Err(HeaderChainError::PrevBlockDigestMismatch(4)),
+            validate_ignoring_pow(network, &headers)
+        );
+    }
+
+    #[test]
+    fn reject_bad_height() {
+        let network = Network::Main;
+        let mut headers = valid_chain(network, 10);
+        let mut header = *headers[6].header();
+        header.height = header.height.next();
+        replace_and_relink(network, &mut headers, 6, header);
+        assert_eq!(
+            Err(HeaderChainError::BlockHeight(6)),
+            validate_ignoring_pow(network, &headers)
+        );
+    }
+
+    #[test]
+    fn reject_too_short_block_time() {
+        let network = Network::Main;
+        let mut headers = valid_chain(network, 10);
+        let parent = headers[2].header();
+        let timestamp = parent.timestamp + network.minimum_block_time() - Timestamp::millis(1);
+        let header = *child_of(network, &headers[2], timestamp).header();
+        replace_and_relink(network, &mut headers, 3, header);
+        assert_eq!(
+            Err(HeaderChainError::MinimumBlockTime(3)),
+            validate_ignoring_pow(network, &headers)
+        );
+    }
+
+    #[test]
+    fn reject_timestamp_before_parent() {
+        let network = Network::Main;
+        let mut headers = valid_chain(network, 10);
+        let mut header = *headers[5].header();
+        header.timestamp = headers[4].header().timestamp - Timestamp::seconds(100);
+        replace_and_relink(network, &mut headers, 5, header);
+        assert_eq!(
+            Err(HeaderChainError::MinimumBlockTime(5)),
+            validate_ignoring_pow(network, &headers)
+        );
+    }
+
+    #[test]
+    fn reject_bad_difficulty() {
+        let network = Network::Main;
+        let mut headers = valid_chain(network, 10);
+        let mut header = *headers[7].header();
+        header.difficulty = Difficulty::MAXIMUM;
+        replace_and_relink(network, &mut headers, 7, header);
+        assert_eq!(
+            Err(HeaderChainError::Difficulty(7)),
+            validate_ignoring_pow(network, &headers)
+        );
+    }
+
+    #[test]
+    fn reject_bad_cumulative_proof_of_work() {
+        let network = Network::Main;
+        let mut headers = valid_chain(network, 10);
+        let mut header = *headers[8].header();
+        header.cumulative_proof_of_work = header.cumulative_proof_of_work + header.difficulty;
+        replace_and_relink(network, &mut headers, 8, header);
+        assert_eq!(
+            Err(HeaderChainError::CumulativeProofOfWork(8)),
+            validate_ignoring_pow(network, &headers)
+        );
+    }
+
+    #[test]
+    fn testnet_difficulty_resets_after_long_pause() {
+        let network = Network::Testnet(0);
+        let mut headers = valid_chain(network, 5);
+        let parent = headers.last().unwrap();
+        let reset_interval = network.difficulty_reset_interval().unwrap();
+        let timestamp = parent.header().timestamp + reset_interval + Timestamp::seconds(1);
+        let child = child_of(network, parent, timestamp);
+        assert_eq!(network.genesis_difficulty(), child.header().difficulty);
+        headers.push(child);
+        assert_eq!(Ok(()), validate_ignoring_pow(network, &headers));
+
+        // Mainnet has no such reset.
+        let network = Network::Main;
+        let parent = valid_chain(network, 5).pop().unwrap();
+        let timestamp = parent.header().timestamp + reset_interval + Timestamp::seconds(1);
+        let mut header = *child_of(network, &parent, timestamp).header();
+        header.difficulty = network.genesis_difficulty();
+        let child = BlockHeaderWithBlockHashWitness::new(header, rand::random());
+        assert_eq!(
+            Err(HeaderChainError::Difficulty(1)),
+            validate_ignoring_pow(network, &[parent, child])
+        );
+    }
+}
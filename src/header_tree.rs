@@ -0,0 +1,382 @@
+//! An in-memory tree of block headers, including forks, that tracks the
+//! canonical chain.
+//!
+//! The canonical tip is the header with the most cumulative proof-of-work; in
+//! case of a tie the header that was seen first wins. Inserting a header
+//! reports whether the tip moved and, if so, which blocks left and joined the
+//! canonical chain.
+//!
+//! Headers are not validated here; use [`crate::header_chain`] for that before
+//! inserting them.
+use std::collections::HashMap;
+
+use thiserror::Error;
+use twenty_first::prelude::Digest;
+
+use crate::block_header::BlockHeader;
+use crate::block_header::BlockHeaderWithBlockHashWitness;
+use crate::block_height::BlockHeight;
+use crate::block_selector::BlockSelectorSource;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum HeaderTreeError {
+    #[error("parent {0} is not in the header tree")]
+    UnknownParent(Digest),
+
+    #[error("header has height {height} but its parent has height {parent_height}")]
+    BlockHeight {
+        height: BlockHeight,
+        parent_height: BlockHeight,
+    },
+}
+
+/// A switch of the canonical chain from one branch to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reorganization {
+    /// The most recent block that both the old and new canonical chain share.
+    pub fork_point: Digest,
+
+    /// The blocks that are no longer canonical, in ascending height order.
+    pub reverted: Vec<Digest>,
+
+    /// The blocks that became canonical, in ascending height order. The last
+    /// one is the new tip.
+    pub applied: Vec<Digest>,
+}
+
+/// The effect that inserting a header had on the canonical chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TipUpdate {
+    /// The tip did not change. The header was either already known or is
+    /// stored on a fork with no more proof-of-work than the canonical chain.
+    Unchanged,
+
+    /// The new header is a child of the old tip, and is the new tip.
+    Extended,
+
+    /// The new header is the new tip, but on a different branch.
+    Reorganized(Reorganization),
+}
+
+#[derive(Debug, Clone)]
+pub struct HeaderTree {
+    headers: HashMap<Digest, BlockHeader>,
+    digests_by_height: HashMap<BlockHeight, Vec<Digest>>,
+
+    /// The canonical chain, from the root up to and including the tip.
+    canonical_chain: Vec<Digest>,
+}
+
+impl HeaderTree {
+    /// Create a tree rooted at the given header. The root is trusted and is
+    /// typically the genesis header, but can be any header.
+    pub fn new(root: BlockHeaderWithBlockHashWitness) -> Self {
+        Self::new_with_digest(root.hash(), *root.header())
+    }
+
+    /// Like [`Self::new`], for callers that already know the block digest.
+    pub fn new_with_digest(root_digest: Digest, root: BlockHeader) -> Self {
+        Self {
+            headers: HashMap::from([(root_digest, root)]),
+            digests_by_height: HashMap::from([(root.height, vec![root_digest])]),
+            canonical_chain: vec![root_digest],
+        }
+    }
+
+    pub fn root(&self) -> Digest {
+        self.canonical_chain[0]
+    }
+
+    pub fn tip(&self) -> Digest {
+        *self.canonical_chain.last().unwrap()
+    }
+
+    pub fn tip_header(&self) -> &BlockHeader {
+        &self.headers[&self.tip()]
+    }
+
+    pub fn get(&self, digest: Digest) -> Option<&BlockHeader> {
+        self.headers.get(&digest)
+    }
+
+    pub fn contains(&self, digest: Digest) -> bool {
+        self.headers.contains_key(&digest)
+    }
+
+    /// The digest of the canonical block at the given height, if any.
+    pub fn canonical_digest(&self, height: BlockHeight) -> Option<Digest> {
+        let root_height = self.headers[&self.root()].height;
+        if height < root_height {
+            return None;
+        }
+
+        let index = usize::try_from(u64::from(height) - u64::from(root_height)).ok()?;
+        self.canonical_chain.get(index).copied()
+    }
+
+    pub fn is_canonical(&self, digest: Digest) -> bool {
+        self.get(digest)
+            .is_some_and(|header| self.canonical_digest(header.height) == Some(digest))
+    }
+
+    /// The digests of all known blocks at the given height, in the order in
+    /// which they were inserted.
+    pub fn digests_at_height(&self, height: BlockHeight) -> &[Digest] {
+        self.digests_by_height
+            .get(&height)
+            .map(|digests| digests.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The digests of all other known blocks at the same height as the given
+    /// one.
+    pub fn sibling_blocks(&self, digest: Digest) -> Vec<Digest> {
+        let Some(header) = self.get(digest) else {
+            return vec![];
+        };
+
+        self.digests_at_height(header.height)
+            .iter()
+            .copied()
+            .filter(|&d| d != digest)
+            .collect()
+    }
+
+    /// Insert a header whose parent is already in the tree, and update the
+    /// canonical chain if needed.
+    pub fn insert(
+        &mut self,
+        header: BlockHeaderWithBlockHashWitness,
+    ) -> Result<TipUpdate, HeaderTreeError> {
+        self.insert_with_digest(header.hash(), *header.header())
+    }
+
+    /// Like [`Self::insert`], for callers that already know the block digest.
+    pub fn insert_with_digest(
+        &mut self,
+        digest: Digest,
+        header: BlockHeader,
+    ) -> Result<TipUpdate, HeaderTreeError> {
+        if self.contains(digest) {
+            return Ok(TipUpdate::Unchanged);
+        }
+
+        let parent = self
+            .get(header.prev_block_digest)
+            .ok_or(HeaderTreeError::UnknownParent(header.prev_block_digest))?;
+        if header.height != parent.height.next() {
+            return Err(HeaderTreeError::BlockHeight {
+                height: header.height,
+                parent_height: parent.height,
+            });
+        }
+
+        self.headers.insert(digest, header);
+        self.digests_by_height
+            .entry(header.height)
+            .or_default()
+            .push(digest);
+
+        if header.cumulative_proof_of_work <= self.tip_header().cumulative_proof_of_work {
+            return Ok(TipUpdate::Unchanged);
+        }
+
+        if header.prev_block_digest == self.tip() {
+            self.canonical_chain.push(digest);
+            return Ok(TipUpdate::Extended);
+        }
+
+        // Walk back to the canonical chain. Terminates because every header in
+        // the tree descends from the root, which is canonical.
+        let mut applied = vec![digest];
+        let mut fork_point = header.prev_block_digest;
+        while !self.is_canonical(fork_point) {
+            applied.push(fork_point);
+            fork_point = self.headers[&fork_point].prev_block_digest;
+        }
+        applied.reverse();
+
+        let num_shared_blocks = self.canonical_chain.len() - self.num_blocks_above(fork_point);
+        let reverted = self.canonical_chain.split_off(num_shared_blocks);
+        self.canonical_chain.extend(applied.iter().copied());
+
+        Ok(TipUpdate::Reorganized(Reorganization {
+            fork_point,
+            reverted,
+            applied,
+        }))
+    }
+
+    /// The number of canonical blocks above the given canonical block.
+    fn num_blocks_above(&self, canonical_digest: Digest) -> usize {
+        let height = self.headers[&canonical_digest].height;
+        let tip_height = self.tip_header().height;
+        (u64::from(tip_height) - u64::from(height)) as usize
+    }
+}
+
+impl BlockSelectorSource for HeaderTree {
+    fn block_digest_for_height(&self, height: BlockHeight) -> Option<Digest> {
+        self.canonical_digest(height)
+    }
+
+    fn block_digest_for_tip(&self) -> Option<Digest> {
+        Some(self.tip())
+    }
+
+    /// Only known if the tree is rooted at the genesis block.
+    fn block_digest_for_genesis(&self) -> Option<Digest> {
+        self.canonical_digest(BlockHeight::genesis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::network::Network;
+    use crate::timestamp::Timestamp;
+
+    fn genesis_tree() -> HeaderTree {
+        HeaderTree::new_with_digest(rand::random(), BlockHeader::genesis(Network::Main))
+    }
+
+    /// Extend the tree with `length` blocks on top of `parent`, and return
+    /// the digests of the new blocks.
+    fn grow(tree: &mut HeaderTree, parent: Digest, length: usize) -> Vec<Digest> {
+        let mut digests = vec![];
+        let mut parent = parent;
+        for _ in 0..length {
+            let parent_header = *tree.get(parent).unwrap();
+            let timestamp = parent_header.timestamp + Timestamp::minutes(10);
+            let header = BlockHeader::template_header(
+                &parent_header,
+                parent,
+                timestamp,
+                Network::Main.target_block_interval(),
+            );
+            let digest = rand::random();
+            tree.insert_with_digest(digest, header).unwrap();
+            digests.push(digest);
+            parent = digest;
+        }
+
+        digests
+    }
+
+    #[test]
+    fn extending_the_tip_moves_the_tip() {
+        let mut tree = genesis_tree();
+        let genesis = tree.root();
+        let chain = grow(&mut tree, genesis, 5);
+
+        assert_eq!(*chain.last().unwrap(), tree.tip());
+        assert!(chain.iter().all(|&digest| tree.is_canonical(digest)));
+        assert_eq!(Some(genesis), tree.block_digest_for_genesis());
+        assert_eq!(Some(chain[2]), tree.block_digest_for_height(3u64.into()));
+        assert_eq!(None, tree.block_digest_for_height(6u64.into()));
+    }
+
+    #[test]
+    fn lighter_fork_is_stored_but_not_canonical() {
+        let mut tree = genesis_tree();
+        let genesis = tree.root();
+        let main = grow(&mut tree, genesis, 5);
+        let fork = grow(&mut tree, main[1], 2);
+
+        assert_eq!(*main.last().unwrap(), tree.tip());
+        assert!(fork.iter().all(|&digest| tree.contains(digest)));
+        assert!(fork.iter().all(|&digest| !tree.is_canonical(digest)));
+        assert_eq!(vec![fork[0]], tree.sibling_blocks(main[2]));
+        assert_eq!(vec![main[3]], tree.sibling_blocks(fork[1]));
+    }
+
+    #[test]
+    fn heavier_fork_causes_reorganization() {
+        let mut tree = genesis_tree();
+        let genesis = tree.root();
+        let main = grow(&mut tree, genesis, 5);
+        let fork = grow(&mut tree, main[1], 3);
+        assert_eq!(
+            *main.last().unwrap(),
+            tree.tip(),
+            "tie must favor first seen"
+        );
+
+        let parent = *fork.last().unwrap();
+        let parent_header = *tree.get(parent).unwrap();
+        let new_tip_header = BlockHeader::template_header(
+            &parent_header,
+            parent,
+            parent_header.timestamp + Timestamp::minutes(10),
+            Network::Main.target_block_interval(),
+        );
+        let new_tip = rand::random();
+        let update = tree.insert_with_digest(new_tip, new_tip_header).unwrap();
+
+        let expected = Reorganization {
+            fork_point: main[1],
+            reverted: main[2..].to_vec(),
+            applied: fork.iter().copied().chain([new_tip]).collect_vec(),
+        };
+        assert_eq!(TipUpdate::Reorganized(expected), update);
+        assert_eq!(new_tip, tree.tip());
+        assert!(main[2..].iter().all(|&digest| !tree.is_canonical(digest)));
+        assert!(fork.iter().all(|&digest| tree.is_canonical(digest)));
+        assert_eq!(Some(fork[0]), tree.canonical_digest(3u64.into()));
+    }
+
+    #[test]
+    fn reinserting_is_a_no_op() {
+        let mut tree = genesis_tree();
+        let genesis = tree.root();
+        let chain = grow(&mut tree, genesis, 2);
+        let header = *tree.get(chain[0]).unwrap();
+
+        assert_eq!(
+            Ok(TipUpdate::Unchanged),
+            tree.insert_with_digest(chain[0], header)
+        );
+        assert_eq!(chain[1], tree.tip());
+        assert_eq!(&[chain[0]], tree.digests_at_height(1u64.into()));
+    }
+
+    #[test]
+    fn reject_orphans_and_bad_heights() {
+        let mut tree = genesis_tree();
+        let genesis = *tree.tip_header();
+
+        let mut orphan = genesis;
+        orphan.prev_block_digest = rand::random();
+        orphan.height = 1u64.into();
+        assert_eq!(
+            Err(HeaderTreeError::UnknownParent(orphan.prev_block_digest)),
+            tree.insert_with_digest(rand::random(), orphan)
+        );
+
+        let mut bad_height = genesis;
+        bad_height.prev_block_digest = tree.root();
+        bad_height.height = 2u64.into();
+        assert_eq!(
+            Err(HeaderTreeError::BlockHeight {
+                height: 2u64.into(),
+                parent_height: 0u64.into()
+            }),
+            tree.insert_with_digest(rand::random(), bad_height)
+        );
+    }
+
+    #[test]
+    fn tree_rooted_above_genesis_has_no_genesis() {
+        let mut root = BlockHeader::genesis(Network::Main);
+        root.height = 100u64.into();
+        let mut tree = HeaderTree::new_with_digest(rand::random(), root);
+        let root_digest = tree.root();
+        let chain = grow(&mut tree, root_digest, 3);
+
+        assert_eq!(None, tree.block_digest_for_genesis());
+        assert_eq!(None, tree.block_digest_for_height(99u64.into()));
+        assert_eq!(Some(chain[0]), tree.block_digest_for_height(101u64.into()));
+    }
+}
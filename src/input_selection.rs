@@ -0,0 +1,361 @@
+//! Selects transaction inputs from a pool of spendable UTXOs.
+//!
+//! Candidates that are still time-locked at the transaction's timestamp, or
+//! that carry no native currency, are never selected.
+use itertools::Itertools;
+use num_traits::CheckedAdd;
+use num_traits::CheckedSub;
+use num_traits::Zero;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::native_currency_amount::NativeCurrencyAmount;
+use crate::timestamp::Timestamp;
+use crate::tx_input::TxInput;
+use crate::tx_input::TxInputList;
+use crate::tx_output::TxOutputList;
+
+/// Determines which of the spendable candidates are used as inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum InputSelectionPolicy {
+    /// Spend the largest UTXOs first.
+    #[default]
+    LargestFirst,
+
+    /// Spend as few UTXOs as possible and, among those, leave little change:
+    /// all but the last input are the largest candidates, and the last one
+    /// is the smallest candidate that covers the remainder.
+    MinimizeInputs,
+
+    /// Spend UTXOs in random order, so that the selection does not reveal
+    /// anything about the wallet beyond the inputs themselves.
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum InputSelectionError {
+    #[error("fee must not be negative, got {0}")]
+    NegativeFee(NativeCurrencyAmount),
+
+    #[error("outputs plus fee exceed the maximum amount")]
+    Overflow,
+
+    #[error("insufficient spendable funds: required {required}, spendable {spendable}")]
+    InsufficientFunds {
+        required: NativeCurrencyAmount,
+        spendable: NativeCurrencyAmount,
+    },
+}
+
+/// Select inputs whose native currency amounts add up to at least `required`.
+///
+/// Candidates that cannot be spent at `timestamp` because of a time-lock are
+/// skipped. Any amount in excess of `required` is change, which the caller
+/// must return to the sender with an additional output.
+pub fn select_inputs<I: Into<TxInput>>(
+    candidates: impl IntoIterator<Item = I>,
+    required: NativeCurrencyAmount,
+    timestamp: Timestamp,
+    policy: InputSelectionPolicy,
+) -> Result<TxInputList, InputSelectionError> {
+    let mut spendable = candidates
+        .into_iter()
+        .map(Into::into)
+        .filter(|input: &TxInput| {
+            input.utxo.can_spend_at(timestamp) && input.native_currency_amount().is_positive()
+        })
+        .collect_vec();
+
+    if !required.is_positive() {
+        return Ok(TxInputList::empty());
+    }
+
+    match policy {
+        InputSelectionPolicy::LargestFirst | InputSelectionPolicy::MinimizeInputs => {
+            spendable.sort_by_key(|input| std::cmp::Reverse(input.native_currency_amount()));
+        }
+        InputSelectionPolicy::Random => spendable.shuffle(&mut rand::rng()),
+    }
+
+    let Some(num_inputs) = num_inputs_to_cover(&spendable, required) else {
+        return Err(InputSelectionError::InsufficientFunds {
+            required,
+            spendable: spendable.iter().map(TxInput::native_currency_amount).sum(),
+        });
+    };
+
+    if policy == InputSelectionPolicy::MinimizeInputs {
+        // The candidates are sorted in descending order, so the last one that
+        // covers what the largest `num_inputs - 1` leave open is the smallest.
+        let covered_by_others = spendable[..num_inputs - 1]
+            .iter()
+            .map(TxInput::native_currency_amount)
+            .sum::<NativeCurrencyAmount>();
+        let remainder = required
+            .checked_sub(&covered_by_others)
+            .unwrap_or_else(NativeCurrencyAmount::zero);
+        let last = (num_inputs - 1..spendable.len())
+            .rev()
+            .find(|&i| spendable[i].native_currency_amount() >= remainder)
+            .unwrap_or(num_inputs - 1);
+        spendable.swap(num_inputs - 1, last);
+    }
+
+    spendable.truncate(num_inputs);
+    Ok(spendable.into())
+}
+
+/// Inputs that cover a transaction's outputs plus fee, and the change that
+/// is left over.
+#[derive(Debug, Clone)]
+pub struct InputSelection {
+    pub tx_inputs: TxInputList,
+
+    /// The amount by which the inputs exceed the outputs plus the fee. Unless
+    /// zero, the transaction is only balanced once an output for this amount
+    /// is added, as prescribed by the sender's
+    /// [ChangePolicy](crate::change_policy::ChangePolicy).
+    pub change: NativeCurrencyAmount,
+}
+
+/// Select inputs that cover the outputs plus the fee.
+///
+/// The selection is not a transaction yet: the caller adds an output for the
+/// [change](InputSelection::change), if any, before assembling the
+/// [TransactionDetails](crate::transaction_details::TransactionDetails).
+pub fn select_inputs_for_outputs<I: Into<TxInput>>(
+    candidates: impl IntoIterator<Item = I>,
+    tx_outputs: &TxOutputList,
+    fee: NativeCurrencyAmount,
+    timestamp: Timestamp,
+    policy: InputSelectionPolicy,
+) -> Result<InputSelection, InputSelectionError> {
+    if fee.is_negative() {
+        return Err(InputSelectionError::NegativeFee(fee));
+    }
+
+    let required = tx_outputs
+        .total_native_coins()
+        .checked_add(&fee)
+        .ok_or(InputSelectionError::Overflow)?;
+    let tx_inputs = select_inputs(candidates, required, timestamp, policy)?;
+    let change = tx_inputs
+        .total_native_coins()
+        .checked_sub(&required)
+        .unwrap_or_else(NativeCurrencyAmount::zero);
+
+    Ok(InputSelection { tx_inputs, change })
+}
+
+/// The length of the shortest prefix of `inputs` that covers `required`.
+fn num_inputs_to_cover(inputs: &[TxInput], required: NativeCurrencyAmount) -> Option<usize> {
+    let mut covered = NativeCurrencyAmount::zero();
+    for (i, input) in inputs.iter().enumerate() {
+        covered = covered + input.native_currency_amount();
+        if covered >= required {
+            return Some(i + 1);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::prelude::Digest;
+    use twenty_first::prelude::Tip5;
+
+    use super::*;
+    use crate::lock_script::LockScript;
+    use crate::lock_script::LockScriptAndWitness;
+    use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+    use crate::time_lock::TimeLock;
+    use crate::unlocked_utxo::UnlockedUtxo;
+    use crate::utxo::Coin;
+    use crate::utxo::Utxo;
+
+    fn input(coins: u32, release_date: Option<Timestamp>) -> TxInput {
+        let mut utxo = Utxo::new_native_currency(
+            LockScript::from(&lock_script_and_witness()),
+            NativeCurrencyAmount::coins(coins),
+        );
+        if let Some(release_date) = release_date {
+            utxo = utxo.with_time_lock(release_date);
+        }
+
+        unlock(utxo)
+    }
+
+    fn lock_script_and_witness() -> LockScriptAndWitness {
+        LockScriptAndWitness::standard_hash_lock_from_preimage(Digest::default())
+    }
+
+    fn unlock(utxo: Utxo) -> TxInput {
+        let membership_proof = MutatorSetAccumulator::default().prove(
+            Tip5::hash(&utxo),
+            Digest::default(),
+            Digest::default(),
+        );
+        UnlockedUtxo::unlock(utxo, lock_script_and_witness(), membership_proof).into()
+    }
+
+    fn amounts(inputs: &TxInputList) -> Vec<NativeCurrencyAmount> {
+        inputs
+            .iter()
+            .map(TxInput::native_currency_amount)
+            .sorted()
+            .collect()
+    }
+
+    fn coins(amounts: &[u32]) -> Vec<NativeCurrencyAmount> {
+        amounts
+            .iter()
+            .map(|&a| NativeCurrencyAmount::coins(a))
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn policies_cover_required_amount() {
+        let now = Timestamp::now();
+        let pool = [1, 5, 7, 20, 30].map(|c| input(c, None));
+        let required = NativeCurrencyAmount::coins(33);
+
+        let largest_first = select_inputs(
+            pool.clone(),
+            required,
+            now,
+            InputSelectionPolicy::LargestFirst,
+        )
+        .unwrap();
+        assert_eq!(coins(&[30, 20]), amounts(&largest_first));
+
+        let minimize_inputs = select_inputs(
+            pool.clone(),
+            required,
+            now,
+            InputSelectionPolicy::MinimizeInputs,
+        )
+        .unwrap();
+        assert_eq!(coins(&[30, 5]), amounts(&minimize_inputs));
+
+        for _ in 0..10 {
+            let random =
+                select_inputs(pool.clone(), required, now, InputSelectionPolicy::Random).unwrap();
+            assert!(random.total_native_coins() >= required);
+        }
+    }
+
+    #[test]
+    fn time_locked_utxos_are_skipped() {
+        let now = Timestamp::now();
+        let pool = [
+            input(10, Some(now + Timestamp::days(1))),
+            input(3, Some(now - Timestamp::days(1))),
+            input(2, None),
+        ];
+
+        let selected = select_inputs(
+            pool.clone(),
+            NativeCurrencyAmount::coins(5),
+            now,
+            InputSelectionPolicy::LargestFirst,
+        )
+        .unwrap();
+        assert_eq!(coins(&[2, 3]), amounts(&selected));
+
+        assert_eq!(
+            Err(InputSelectionError::InsufficientFunds {
+                required: NativeCurrencyAmount::coins(6),
+                spendable: NativeCurrencyAmount::coins(5),
+            }),
+            select_inputs(
+                pool,
+                NativeCurrencyAmount::coins(6),
+                now,
+                InputSelectionPolicy::LargestFirst,
+            )
+            .map(|inputs| amounts(&inputs))
+        );
+    }
+
+    #[test]
+    fn every_time_lock_must_have_expired() {
+        let now = Timestamp::now();
+        let utxo_with_coins = |coins: u32, time_locks: Vec<Coin>| {
+            let mut all_coins = vec![Coin::new_native_currency(NativeCurrencyAmount::coins(
+                coins,
+            ))];
+            all_coins.extend(time_locks);
+            Utxo::new(LockScript::from(&lock_script_and_witness()), all_coins)
+        };
+        let past = now - Timestamp::days(1);
+        let future = now + Timestamp::days(1);
+        let malformed_time_lock = Coin {
+            type_script_hash: TimeLock.hash(),
+            state: vec![],
+        };
+        let pool = [
+            utxo_with_coins(10, vec![TimeLock::until(past), TimeLock::until(future)]),
+            utxo_with_coins(8, vec![TimeLock::until(past), malformed_time_lock]),
+            utxo_with_coins(3, vec![TimeLock::until(past), TimeLock::until(past)]),
+        ];
+        for utxo in &pool {
+            assert_eq!(
+                TimeLock::violations(std::slice::from_ref(utxo), now).is_empty(),
+                utxo.can_spend_at(now)
+            );
+        }
+
+        let selected = select_inputs(
+            pool.map(unlock),
+            NativeCurrencyAmount::coins(3),
+            now,
+            InputSelectionPolicy::LargestFirst,
+        )
+        .unwrap();
+        assert_eq!(coins(&[3]), amounts(&selected));
+    }
+
+    #[test]
+    fn selection_reports_change() {
+        let now = Timestamp::now();
+        let pool = [4, 4, 4].map(|c| input(c, None));
+        let selection = select_inputs_for_outputs(
+            pool.clone(),
+            &TxOutputList::default(),
+            NativeCurrencyAmount::coins(5),
+            now,
+            InputSelectionPolicy::LargestFirst,
+        )
+        .unwrap();
+        assert_eq!(2, selection.tx_inputs.len());
+        assert_eq!(NativeCurrencyAmount::coins(3), selection.change);
+
+        let exact = select_inputs_for_outputs(
+            pool.clone(),
+            &TxOutputList::default(),
+            NativeCurrencyAmount::coins(8),
+            now,
+            InputSelectionPolicy::LargestFirst,
+        )
+        .unwrap();
+        assert_eq!(NativeCurrencyAmount::zero(), exact.change);
+
+        assert_eq!(
+            Err(InputSelectionError::NegativeFee(
+                -NativeCurrencyAmount::coins(1)
+            )),
+            select_inputs_for_outputs(
+                pool,
+                &TxOutputList::default(),
+                -NativeCurrencyAmount::coins(1),
+                now,
+                InputSelectionPolicy::LargestFirst,
+            )
+            .map(|selection| selection.change)
+        );
+    }
+}
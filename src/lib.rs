@@ -1,18 +1,27 @@
 pub(crate) use twenty_first;
 pub mod address;
 pub mod announcement;
+pub mod block;
+pub mod block_appendix;
+pub mod block_body;
 pub mod block_header;
 pub mod block_height;
 pub mod block_info;
+pub mod block_kernel;
 pub mod block_selector;
 pub mod change_policy;
 pub mod dashboard_overview_data_from_client;
 pub mod difficulty_control;
+pub mod difficulty_simulator;
 pub mod guesser_receiver_data;
+pub mod header_chain;
+pub mod header_tree;
 pub mod incoming_utxo;
+pub mod input_selection;
 pub mod lock_script;
 pub mod mast_hash;
 pub mod mempool_transaction_info;
+pub mod mining_analytics;
 pub mod mining_status;
 pub mod mutator_set;
 pub mod native_currency;
@@ -20,19 +29,24 @@ pub mod native_currency_amount;
 pub mod network;
 pub mod output_format;
 pub mod pow;
+pub mod primitive_witness;
+pub mod proof_collection;
 pub mod secret_key_material;
 #[cfg(test)]
 pub mod test_shared;
 pub mod time_lock;
 pub mod timestamp;
+pub mod transaction;
 pub mod transaction_details;
 pub mod transaction_kernel;
 pub mod transaction_kernel_id;
+pub mod transaction_proof;
 pub mod transaction_proof_type;
 pub mod triton_vm;
 pub mod tx_input;
 pub mod tx_output;
 pub mod tx_proving_capability;
+pub mod type_script;
 pub mod unlocked_utxo;
 pub mod ui_utxo;
 pub mod utxo;
@@ -0,0 +1,177 @@
+//! Estimates of network guessing rate and block times, derived from a window
+//! of consecutive blocks.
+//!
+//! A guess succeeds with probability 1/difficulty, so finding a block takes
+//! `difficulty` guesses on average. Guessing rates are in guesses per second.
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+use crate::block_header::BlockHeader;
+use crate::block_info::BlockInfo;
+use crate::difficulty_control::Difficulty;
+use crate::difficulty_control::ProofOfWork;
+use crate::timestamp::Timestamp;
+
+/// The per-block data the estimates are based on.
+pub trait BlockSample {
+    fn timestamp(&self) -> Timestamp;
+    fn difficulty(&self) -> Difficulty;
+    fn cumulative_proof_of_work(&self) -> ProofOfWork;
+}
+
+impl BlockSample for BlockHeader {
+    fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn cumulative_proof_of_work(&self) -> ProofOfWork {
+        self.cumulative_proof_of_work
+    }
+}
+
+impl BlockSample for BlockInfo {
+    fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn cumulative_proof_of_work(&self) -> ProofOfWork {
+        self.cumulative_proof_of_work
+    }
+}
+
+/// The time between the first and last block of the window, if the window
+/// holds at least two blocks and the timestamps increase.
+fn elapsed<B: BlockSample>(window: &[B]) -> Option<Timestamp> {
+    let [first, .., last] = window else {
+        return None;
+    };
+
+    (last.timestamp() > first.timestamp()).then(|| last.timestamp() - first.timestamp())
+}
+
+/// Average time between consecutive blocks in the window, which must be
+/// ordered by ascending height.
+pub fn average_block_interval<B: BlockSample>(window: &[B]) -> Option<Timestamp> {
+    let elapsed = elapsed(window)?;
+    let num_intervals = window.len() as u64 - 1;
+    Some(Timestamp::millis(elapsed.to_millis() / num_intervals))
+}
+
+/// Estimate the guessing rate of the whole network from the work done to
+/// produce the blocks of the window, which must be ordered by ascending height.
+///
+/// The work is the difference in cumulative proof-of-work between the last
+/// and first block, *i.e.*, the sum of the difficulties of all blocks but the
+/// last.
+pub fn estimated_network_guessing_rate<B: BlockSample>(window: &[B]) -> Option<f64> {
+    let elapsed = elapsed(window)?;
+    let last_pow = BigUint::from(window.last()?.cumulative_proof_of_work());
+    let first_pow = BigUint::from(window.first()?.cumulative_proof_of_work());
+    if last_pow < first_pow {
+        return None;
+    }
+
+    let work = (last_pow - first_pow).to_f64()?;
+    Some(work / elapsed.as_duration().as_secs_f64())
+}
+
+/// The expected time until a block of the given difficulty is found, at the
+/// given guessing rate. Because guessing is memoryless, this does not depend on
+/// how long ago the previous block was found.
+pub fn expected_time_to_next_block(
+    difficulty: Difficulty,
+    guessing_rate: f64,
+) -> Option<Timestamp> {
+    if guessing_rate <= 0.0 {
+        return None;
+    }
+
+    let expected_num_guesses = BigUint::from(difficulty).to_f64()?;
+    let millis = 1000.0 * expected_num_guesses / guessing_rate;
+    Some(Timestamp::millis(millis.to_u64()?))
+}
+
+/// The probability that a guesser with the given guessing rate finds a block
+/// of the given difficulty within the given time span.
+pub fn probability_of_finding_block(
+    difficulty: Difficulty,
+    guessing_rate: f64,
+    time_span: Timestamp,
+) -> f64 {
+    let Some(expected_num_guesses) = BigUint::from(difficulty).to_f64() else {
+        return 0.0;
+    };
+    let num_guesses = guessing_rate.max(0.0) * time_span.as_duration().as_secs_f64();
+
+    // 1 - (1 - 1/d)^n, which for large d is 1 - e^(-n/d)
+    -(-num_guesses / expected_num_guesses).exp_m1()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+
+    /// Headers with constant difficulty, spaced `interval` apart.
+    fn window(difficulty: Difficulty, interval: Timestamp, length: usize) -> Vec<BlockHeader> {
+        let mut header = BlockHeader::genesis(Network::Main);
+        header.difficulty = difficulty;
+        let mut headers = vec![header];
+        for _ in 1..length {
+            header.timestamp += interval;
+            header.cumulative_proof_of_work = header.cumulative_proof_of_work + difficulty;
+            headers.push(header);
+        }
+
+        headers
+    }
+
+    #[test]
+    fn constant_rate_is_recovered() {
+        let difficulty = Difficulty::from(600_000u32);
+        let headers = window(difficulty, Timestamp::minutes(10), 50);
+
+        assert_eq!(
+            Some(Timestamp::minutes(10)),
+            average_block_interval(&headers)
+        );
+
+        let rate = estimated_network_guessing_rate(&headers).unwrap();
+        assert!((rate - 1000.0).abs() < 1e-6, "rate: {rate}");
+
+        assert_eq!(
+            Some(Timestamp::minutes(10)),
+            expected_time_to_next_block(difficulty, rate)
+        );
+    }
+
+    #[test]
+    fn too_small_windows_give_no_estimate() {
+        let headers = window(Difficulty::MINIMUM, Timestamp::minutes(10), 1);
+        assert_eq!(None, average_block_interval(&headers));
+        assert_eq!(None, estimated_network_guessing_rate(&headers));
+        assert_eq!(None, estimated_network_guessing_rate::<BlockHeader>(&[]));
+    }
+
+    #[test]
+    fn probability_of_finding_block_is_sane() {
+        let difficulty = Difficulty::from(1_000_000u32);
+        let one_day = Timestamp::days(1);
+        assert_eq!(0.0, probability_of_finding_block(difficulty, 0.0, one_day));
+
+        // expected number of guesses equals the difficulty
+        let p = probability_of_finding_block(difficulty, 1_000_000.0 / 86_400.0, one_day);
+        assert!((p - (1.0 - (-1.0f64).exp())).abs() < 1e-9, "p: {p}");
+
+        let p_large = probability_of_finding_block(difficulty, 1e9, one_day);
+        assert!(p_large > 0.999_999);
+    }
+}
@@ -10,7 +10,7 @@ use std::time::SystemTime;
 use serde::Deserialize;
 use serde::Serialize;
 
-// use crate::models::blockchain::block::Block;
+use crate::block::Block;
 use crate::native_currency_amount::NativeCurrencyAmount;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -22,9 +22,8 @@ pub struct GuessingWorkInfo {
     pub(crate) total_guesser_fee: NativeCurrencyAmount,
 }
 
-/*
 impl GuessingWorkInfo {
-    pub(crate) fn new(work_start: SystemTime, block: &Block) -> Self {
+    pub fn new(work_start: SystemTime, block: &Block) -> Self {
         Self {
             work_start,
             num_inputs: block.body().transaction_kernel.inputs.len(),
@@ -34,7 +33,6 @@ impl GuessingWorkInfo {
         }
     }
 }
-*/
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ComposingWorkInfo {
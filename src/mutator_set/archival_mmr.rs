@@ -0,0 +1,206 @@
+use itertools::Itertools;
+use twenty_first::prelude::*;
+use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+
+/// A Merkle Mountain Range that keeps all of its nodes in memory, and can
+/// therefore produce membership proofs for any of its leafs.
+///
+/// Nodes are stored layer by layer: `layers[0]` holds the leafs, and
+/// `layers[h][i]` is the parent of `layers[h-1][2i]` and `layers[h-1][2i+1]`.
+/// A node exists only once both of its children exist, so the peaks are
+/// exactly the nodes without a parent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchivalMmr {
+    layers: Vec<Vec<Digest>>,
+}
+
+impl ArchivalMmr {
+    pub fn new_from_leafs(leafs: Vec<Digest>) -> Self {
+        let mut archival_mmr = Self::default();
+        for leaf in leafs {
+            archival_mmr.append(leaf);
+        }
+
+        archival_mmr
+    }
+
+    pub fn num_leafs(&self) -> u64 {
+        self.layers.first().map_or(0, |leafs| leafs.len() as u64)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_leafs() == 0
+    }
+
+    /// Get the leaf with the given index.
+    ///
+    /// # Panics
+    ///
+    ///  - If the leaf index is out of bounds.
+    pub fn get_leaf(&self, leaf_index: u64) -> Digest {
+        self.layers[0][leaf_index as usize]
+    }
+
+    /// Return the peaks, largest tree first.
+    pub fn peaks(&self) -> Vec<Digest> {
+        let num_leafs = self.num_leafs();
+        let mut peaks = vec![];
+        let mut num_covered_leafs = 0;
+        for height in (0..u64::BITS).rev() {
+            let tree_width = 1 << height;
+            if num_leafs & tree_width != 0 {
+                peaks.push(self.layers[height as usize][(num_covered_leafs >> height) as usize]);
+                num_covered_leafs += tree_width;
+            }
+        }
+
+        peaks
+    }
+
+    pub fn to_accumulator(&self) -> MmrAccumulator {
+        MmrAccumulator::init(self.peaks(), self.num_leafs())
+    }
+
+    /// Append a leaf and return its membership proof.
+    pub fn append(&mut self, new_leaf: Digest) -> MmrMembershipProof {
+        let mut height = 0;
+        let mut node = new_leaf;
+        loop {
+            if self.layers.len() == height {
+                self.layers.push(vec![]);
+            }
+            self.layers[height].push(node);
+
+            let layer = &self.layers[height];
+            if layer.len() % 2 == 1 {
+                break;
+            }
+            node = Tip5::hash_pair(layer[layer.len() - 2], layer[layer.len() - 1]);
+            height += 1;
+        }
+
+        self.prove_membership(self.num_leafs() - 1)
+    }
+
+    /// Replace the leaf with the given index, updating all its ancestors.
+    ///
+    /// # Panics
+    ///
+    ///  - If the leaf index is out of bounds.
+    pub fn mutate_leaf(&mut self, leaf_index: u64, new_leaf: Digest) {
+        let mut node_index = leaf_index as usize;
+        self.layers[0][node_index] = new_leaf;
+        for height in 1..self.layers.len() {
+            let parent_index = node_index / 2;
+            if self.layers[height].len() <= parent_index {
+                break;
+            }
+
+            let children = &self.layers[height - 1];
+            let left_child = children[2 * parent_index];
+            let right_child = children[2 * parent_index + 1];
+            self.layers[height][parent_index] = Tip5::hash_pair(left_child, right_child);
+            node_index = parent_index;
+        }
+    }
+
+    /// Remove the most recently added leaf, along with every node that
+    /// depends on it. Returns the removed leaf, if any.
+    pub fn remove_last_leaf(&mut self) -> Option<Digest> {
+        let last_leaf = self.layers.first_mut()?.pop()?;
+        for height in 1..self.layers.len() {
+            let num_nodes = self.layers[height - 1].len() / 2;
+            self.layers[height].truncate(num_nodes);
+        }
+        while self.layers.last().is_some_and(|layer| layer.is_empty()) {
+            self.layers.pop();
+        }
+
+        Some(last_leaf)
+    }
+
+    /// Produce a membership proof for the leaf with the given index.
+    ///
+    /// # Panics
+    ///
+    ///  - If the leaf index is out of bounds.
+    pub fn prove_membership(&self, leaf_index: u64) -> MmrMembershipProof {
+        assert!(
+            leaf_index < self.num_leafs(),
+            "leaf index {leaf_index} out of bounds for MMR with {} leafs",
+            self.num_leafs()
+        );
+
+        let mut node_index = leaf_index as usize;
+        let authentication_path = (0..self.layers.len() - 1)
+            .map_while(|height| {
+                let has_parent = self.layers[height + 1].len() > node_index / 2;
+                let sibling = has_parent.then(|| self.layers[height][node_index ^ 1]);
+                node_index /= 2;
+                sibling
+            })
+            .collect_vec();
+
+        MmrMembershipProof::new(authentication_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::util_types::mmr::mmr_trait::LeafMutation;
+    use twenty_first::util_types::mmr::mmr_trait::Mmr;
+
+    use super::*;
+
+    fn random_leafs(n: usize) -> Vec<Digest> {
+        (0..n).map(|_| rand::random()).collect()
+    }
+
+    #[test]
+    fn agrees_with_accumulator() {
+        for num_leafs in [0, 1, 2, 3, 7, 8, 9, 31, 64, 100] {
+            let leafs = random_leafs(num_leafs);
+            let archival_mmr = ArchivalMmr::new_from_leafs(leafs.clone());
+            let mmra = MmrAccumulator::new_from_leafs(leafs.clone());
+            assert_eq!(mmra, archival_mmr.to_accumulator());
+
+            for (leaf_index, leaf) in leafs.into_iter().enumerate() {
+                let leaf_index = leaf_index as u64;
+                let mp = archival_mmr.prove_membership(leaf_index);
+                assert!(mp.verify(leaf_index, leaf, &mmra.peaks(), mmra.num_leafs()));
+            }
+        }
+    }
+
+    #[test]
+    fn mutate_leaf_agrees_with_accumulator() {
+        let leafs = random_leafs(45);
+        let mut archival_mmr = ArchivalMmr::new_from_leafs(leafs.clone());
+        let mut mmra = MmrAccumulator::new_from_leafs(leafs);
+
+        for leaf_index in [0, 13, 31, 32, 44] {
+            let new_leaf: Digest = rand::random();
+            let mp = archival_mmr.prove_membership(leaf_index);
+            archival_mmr.mutate_leaf(leaf_index, new_leaf);
+            mmra.mutate_leaf(LeafMutation::new(leaf_index, new_leaf, mp));
+            assert_eq!(mmra, archival_mmr.to_accumulator());
+        }
+    }
+
+    #[test]
+    fn remove_last_leaf_undoes_append() {
+        let mut archival_mmr = ArchivalMmr::new_from_leafs(random_leafs(37));
+        let snapshots = (0..20)
+            .map(|_| {
+                let snapshot = archival_mmr.clone();
+                archival_mmr.append(rand::random());
+                snapshot
+            })
+            .collect_vec();
+
+        for snapshot in snapshots.into_iter().rev() {
+            archival_mmr.remove_last_leaf().unwrap();
+            assert_eq!(snapshot, archival_mmr);
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use itertools::Itertools;
+use twenty_first::prelude::*;
+
+use super::MutatorSetError;
+use super::active_window::ActiveWindow;
+use super::addition_record::AdditionRecord;
+use super::archival_mmr::ArchivalMmr;
+use super::chunk::Chunk;
+use super::chunk_dictionary::ChunkDictionary;
+use super::commit;
+use super::ms_membership_proof::MsMembershipProof;
+use super::mutator_set_accumulator::MutatorSetAccumulator;
+use super::removal_record::RemovalRecord;
+use super::removal_record::absolute_index_set::AbsoluteIndexSet;
+use super::shared::BATCH_SIZE;
+use super::shared::CHUNK_SIZE;
+use super::shared::WINDOW_SIZE;
+
+/// A mutator set that keeps the full AOCL and SWBF MMRs, as well as every
+/// chunk of the inactive part of the SWBF, in memory.
+///
+/// In contrast to the [`MutatorSetAccumulator`], this can produce membership
+/// proofs for any item ever added, and the MMR membership proofs for chunks
+/// that removal records must carry. Everything is held in memory, so this is
+/// meant for tests and local tooling rather than for a full chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchivalMutatorSet {
+    aocl: ArchivalMmr,
+    swbf_inactive: ArchivalMmr,
+    swbf_active: ActiveWindow,
+    chunks: Vec<Chunk>,
+}
+
+impl ArchivalMutatorSet {
+    /// The append-only commitment list.
+    pub fn aocl(&self) -> &ArchivalMmr {
+        &self.aocl
+    }
+
+    /// The MMR of the chunks of the inactive part of the SWBF.
+    pub fn swbf_inactive(&self) -> &ArchivalMmr {
+        &self.swbf_inactive
+    }
+
+    /// The active part of the SWBF.
+    pub fn swbf_active(&self) -> &ActiveWindow {
+        &self.swbf_active
+    }
+
+    /// Return the accumulator this archival mutator set corresponds to.
+    pub fn accumulator(&self) -> MutatorSetAccumulator {
+        MutatorSetAccumulator {
+            aocl: self.aocl.to_accumulator(),
+            swbf_inactive: self.swbf_inactive.to_accumulator(),
+            swbf_active: self.swbf_active.clone(),
+        }
+    }
+
+    /// Commit to the mutator set as a whole.
+    pub fn hash(&self) -> Digest {
+        self.accumulator().hash()
+    }
+
+    /// Get the chunk of the inactive part of the SWBF with the given index.
+    pub fn get_chunk(&self, chunk_index: u64) -> Option<&Chunk> {
+        self.chunks.get(usize::try_from(chunk_index).ok()?)
+    }
+
+    /// See [`MutatorSetAccumulator::get_batch_index`].
+    fn batch_index(&self) -> u64 {
+        match self.aocl.num_leafs() {
+            0 => 0,
+            n => (n - 1) / u64::from(BATCH_SIZE),
+        }
+    }
+
+    /// Generate a membership proof for an item that is about to be added to
+    /// the mutator set. See [`MutatorSetAccumulator::prove`].
+    pub fn prove(
+        &self,
+        item: Digest,
+        sender_randomness: Digest,
+        receiver_preimage: Digest,
+    ) -> MsMembershipProof {
+        self.accumulator()
+            .prove(item, sender_randomness, receiver_preimage)
+    }
+
+    /// Produce a membership proof, valid relative to the current state, for
+    /// an item that was added at the given AOCL leaf index.
+    ///
+    /// Returns an error if the leaf index is out of bounds, or if the item,
+    /// sender randomness, and receiver preimage do not commit to the AOCL leaf
+    /// at that index.
+    pub fn restore_membership_proof(
+        &self,
+        item: Digest,
+        sender_randomness: Digest,
+        receiver_preimage: Digest,
+        aocl_leaf_index: u64,
+    ) -> Result<MsMembershipProof, MutatorSetError> {
+        let leaf_count = self.aocl.num_leafs();
+        if aocl_leaf_index >= leaf_count {
+            return Err(MutatorSetError::RequestedAoclAuthPathOutOfBounds {
+                leaf_index: aocl_leaf_index,
+                leaf_count,
+            });
+        }
+
+        let canonical_commitment =
+            commit(item, sender_randomness, receiver_preimage.hash()).canonical_commitment;
+        if self.aocl.get_leaf(aocl_leaf_index) != canonical_commitment {
+            return Err(MutatorSetError::AoclLeafMismatch { aocl_leaf_index });
+        }
+
+        let absolute_indices =
+            AbsoluteIndexSet::compute(item, sender_randomness, receiver_preimage, aocl_leaf_index);
+        let (inactive_indices, _active_indices) =
+            absolute_indices.split_by_activity(&self.accumulator())?;
+        let target_chunks = inactive_indices
+            .into_keys()
+            .sorted()
+            .map(|chunk_index| {
+                let mmr_mp = self.swbf_inactive.prove_membership(chunk_index);
+                let chunk = self.chunks[chunk_index as usize].clone();
+                (chunk_index, (mmr_mp, chunk))
+            })
+            .collect_vec();
+
+        Ok(MsMembershipProof {
+            sender_randomness,
+            receiver_preimage,
+            auth_path_aocl: self.aocl.prove_membership(aocl_leaf_index),
+            aocl_leaf_index,
+            target_chunks: ChunkDictionary::new(target_chunks),
+        })
+    }
+
+    /// Check if an item is a member of the mutator set.
+    pub fn verify(&self, item: Digest, membership_proof: &MsMembershipProof) -> bool {
+        self.accumulator().verify(item, membership_proof)
+    }
+
+    /// Generate a removal record with which to update the set commitment.
+    pub fn drop(&self, item: Digest, membership_proof: &MsMembershipProof) -> RemovalRecord {
+        self.accumulator().drop(item, membership_proof)
+    }
+
+    /// Check if a removal record can be applied to the mutator set. See
+    /// [`MutatorSetAccumulator::can_remove`].
+    pub fn can_remove(&self, removal_record: &RemovalRecord) -> bool {
+        self.accumulator().can_remove(removal_record)
+    }
+
+    /// Add an item to the mutator set.
+    pub fn add(&mut self, addition_record: &AdditionRecord) {
+        let item_index = self.aocl.num_leafs();
+        self.aocl.append(addition_record.canonical_commitment);
+
+        if !MutatorSetAccumulator::window_slides(item_index) {
+            return;
+        }
+
+        let new_chunk = self.swbf_active.slid_chunk();
+        self.swbf_inactive.append(Tip5::hash(&new_chunk));
+        self.chunks.push(new_chunk);
+        self.swbf_active.slide_window();
+    }
+
+    /// Remove an item from the mutator set.
+    ///
+    /// Only the removal record's indices are used; the chunks it carries are
+    /// ignored in favor of the ones stored here.
+    ///
+    /// Returns an error, and leaves the mutator set unchanged, if any index
+    /// lies beyond the active window.
+    pub fn remove(&mut self, removal_record: &RemovalRecord) -> Result<(), MutatorSetError> {
+        self.apply_indices(removal_record, Chunk::insert, ActiveWindow::insert)
+    }
+
+    /// Revert the most recent addition to the mutator set.
+    ///
+    /// # Panics
+    ///
+    ///  - If the mutator set is empty.
+    ///  - If the addition record is not the one that was added last.
+    pub fn revert_add(&mut self, addition_record: &AdditionRecord) {
+        let removed_index = self
+            .aocl
+            .num_leafs()
+            .checked_sub(1)
+            .expect("cannot revert addition to empty mutator set");
+
+        if MutatorSetAccumulator::window_slides_back(removed_index) {
+            let chunk = self
+                .chunks
+                .pop()
+                .expect("inactive part of SWBF must be non-empty when window slides back");
+            self.swbf_active.slide_window_back(&chunk);
+            self.swbf_inactive.remove_last_leaf();
+        }
+
+        let removed_leaf = self.aocl.remove_last_leaf();
+        assert_eq!(
+            Some(addition_record.canonical_commitment),
+            removed_leaf,
+            "reverted addition record must be the last one added"
+        );
+    }
+
+    /// Revert a removal from the mutator set.
+    ///
+    /// Removal records must be reverted in the opposite order of application,
+    /// and additions applied after the removal must be reverted first.
+    ///
+    /// Returns an error, and leaves the mutator set unchanged, if any index
+    /// lies beyond the active window.
+    pub fn revert_remove(&mut self, removal_record: &RemovalRecord) -> Result<(), MutatorSetError> {
+        self.apply_indices(removal_record, Chunk::remove_once, ActiveWindow::remove)
+    }
+
+    /// Apply `chunk_op` or `window_op` to every index of the removal record,
+    /// depending on whether it lives in the inactive or active part of the
+    /// SWBF, and update the SWBF MMR for all modified chunks.
+    fn apply_indices(
+        &mut self,
+        removal_record: &RemovalRecord,
+        chunk_op: fn(&mut Chunk, u32),
+        window_op: fn(&mut ActiveWindow, u32),
+    ) -> Result<(), MutatorSetError> {
+        let batch_index = self.batch_index();
+        let active_window_start = u128::from(batch_index) * u128::from(CHUNK_SIZE);
+
+        // every chunk before the active window is stored, so only indices
+        // beyond the active window are unknown
+        let max_chunk_index = batch_index + u64::from(WINDOW_SIZE / CHUNK_SIZE) - 1;
+        let chunkidx_to_indices = removal_record.get_chunkidx_to_indices_dict();
+        if let Some(&saw_chunk_index) = chunkidx_to_indices.keys().find(|&&k| k > max_chunk_index) {
+            return Err(MutatorSetError::AbsoluteRemovalIndexIsFutureIndex {
+                current_max_chunk_index: max_chunk_index,
+                saw_chunk_index,
+            });
+        }
+
+        for (chunk_index, indices) in chunkidx_to_indices {
+            if chunk_index >= batch_index {
+                for index in indices {
+                    window_op(&mut self.swbf_active, (index - active_window_start) as u32);
+                }
+
+                continue;
+            }
+
+            let chunk = &mut self.chunks[chunk_index as usize];
+            for index in indices {
+                chunk_op(chunk, (index % u128::from(CHUNK_SIZE)) as u32);
+            }
+            let new_leaf = Tip5::hash(&*chunk);
+            self.swbf_inactive.mutate_leaf(chunk_index, new_leaf);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutator_set::shared::BATCH_SIZE;
+
+    fn random_item() -> (Digest, Digest, Digest) {
+        let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+        (item, sender_randomness, receiver_preimage)
+    }
+
+    #[test]
+    fn accumulator_agrees_with_mutator_set_accumulator() {
+        let mut archival = ArchivalMutatorSet::default();
+        let mut msa = MutatorSetAccumulator::default();
+        assert_eq!(msa, archival.accumulator());
+
+        let mut items_and_proofs = vec![];
+        for _ in 0..3 * BATCH_SIZE + 2 {
+            let (item, sender_randomness, receiver_preimage) = random_item();
+            let addition_record = commit(item, sender_randomness, receiver_preimage.hash());
+            let mp = archival.prove(item, sender_randomness, receiver_preimage);
+            assert_eq!(mp, msa.prove(item, sender_randomness, receiver_preimage));
+
+            archival.add(&addition_record);
+            msa.add(&addition_record);
+            assert_eq!(msa, archival.accumulator());
+            assert_eq!(msa.hash(), archival.hash());
+            items_and_proofs.push((item, sender_randomness, receiver_preimage));
+        }
+
+        // remove every third item, newest first, so that both the active
+        // window and the chunks of the inactive part are updated
+        for (aocl_leaf_index, (item, sender_randomness, receiver_preimage)) in
+            items_and_proofs.into_iter().enumerate().rev().step_by(3)
+        {
+            let mp = archival
+                .restore_membership_proof(
+                    item,
+                    sender_randomness,
+                    receiver_preimage,
+                    aocl_leaf_index as u64,
+                )
+                .unwrap();
+            assert!(archival.verify(item, &mp));
+            assert!(msa.verify(item, &mp));
+
+            let removal_record = archival.drop(item, &mp);
+            assert!(archival.can_remove(&removal_record));
+            archival.remove(&removal_record).unwrap();
+            msa.remove(&removal_record);
+            assert_eq!(msa, archival.accumulator());
+            assert!(!archival.verify(item, &mp));
+        }
+    }
+
+    #[test]
+    fn restore_membership_proof_rejects_bad_requests() {
+        let mut archival = ArchivalMutatorSet::default();
+        let (item, sender_randomness, receiver_preimage) = random_item();
+        archival.add(&commit(item, sender_randomness, receiver_preimage.hash()));
+
+        assert_eq!(
+            Err(MutatorSetError::RequestedAoclAuthPathOutOfBounds {
+                leaf_index: 1,
+                leaf_count: 1
+            }),
+            archival.restore_membership_proof(item, sender_randomness, receiver_preimage, 1)
+        );
+        assert_eq!(
+            Err(MutatorSetError::AoclLeafMismatch { aocl_leaf_index: 0 }),
+            archival.restore_membership_proof(sender_randomness, item, receiver_preimage, 0)
+        );
+    }
+
+    #[test]
+    fn remove_rejects_future_indices() {
+        let mut archival = ArchivalMutatorSet::default();
+        let (item, sender_randomness, receiver_preimage) = random_item();
+        archival.add(&commit(item, sender_randomness, receiver_preimage.hash()));
+
+        // an index set computed for an AOCL leaf far in the future
+        let future_leaf_index = u64::from(BATCH_SIZE) * u64::from(WINDOW_SIZE / CHUNK_SIZE);
+        let removal_record = RemovalRecord {
+            absolute_indices: AbsoluteIndexSet::compute(
+                item,
+                sender_randomness,
+                receiver_preimage,
+                future_leaf_index,
+            ),
+            target_chunks: ChunkDictionary::empty(),
+        };
+
+        let before = archival.clone();
+        assert!(matches!(
+            archival.remove(&removal_record),
+            Err(MutatorSetError::AbsoluteRemovalIndexIsFutureIndex { .. })
+        ));
+        assert!(archival.revert_remove(&removal_record).is_err());
+        assert_eq!(before, archival);
+    }
+
+    #[test]
+    fn reverting_restores_earlier_state() {
+        let mut archival = ArchivalMutatorSet::default();
+        let mut snapshots_and_addition_records = vec![];
+        let mut add_random_item = |archival: &mut ArchivalMutatorSet| {
+            let (item, sender_randomness, receiver_preimage) = random_item();
+            let addition_record = commit(item, sender_randomness, receiver_preimage.hash());
+            snapshots_and_addition_records.push((archival.clone(), addition_record));
+            archival.add(&addition_record);
+            (item, sender_randomness, receiver_preimage)
+        };
+
+        for _ in 0..2 * BATCH_SIZE {
+            add_random_item(&mut archival);
+        }
+        let (item, sender_randomness, receiver_preimage) = add_random_item(&mut archival);
+        for _ in 0..BATCH_SIZE {
+            add_random_item(&mut archival);
+        }
+
+        let mp = archival
+            .restore_membership_proof(
+                item,
+                sender_randomness,
+                receiver_preimage,
+                u64::from(2 * BATCH_SIZE),
+            )
+            .unwrap();
+        let removal_record = archival.drop(item, &mp);
+        let before_removal = archival.clone();
+        archival.remove(&removal_record).unwrap();
+        assert_ne!(before_removal.hash(), archival.hash());
+        archival.revert_remove(&removal_record).unwrap();
+        assert_eq!(before_removal, archival);
+
+        for (snapshot, addition_record) in snapshots_and_addition_records.into_iter().rev() {
+            archival.revert_add(&addition_record);
+            assert_eq!(snapshot, archival);
+        }
+        assert_eq!(ArchivalMutatorSet::default(), archival);
+    }
+}
@@ -3,7 +3,33 @@ use get_size2::GetSize;
 use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
+use thiserror::Error;
 use twenty_first::prelude::*;
+
+/// "Hard" max on the number of elements in a packed [`Chunk`].
+/// Based on the Chernoff bound, the probability of finding a [`Chunk`] with
+/// 4096 elements or more is less than 2^{-4000}. So without loss of generality,
+/// a [`Chunk`] will never have 4096 elements. Packing a [`Chunk`] can therefore
+/// result in (4095+1) * 12 / 32 = 1536 u32s.
+///                           '--- u32 width
+///                       '------- width of packed element and length indicator
+///                 '------------- length indicator
+///              '---------------- max # elements
+const MAX_PACKED_LENGTH: usize = 1536;
+const MAX_UNPACKED_LENGTH: usize = 4095;
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+pub enum ChunkUnpackError {
+    #[error("payload is too large -- packed chunk can never be more than {MAX_PACKED_LENGTH} u32s")]
+    PayloadTooBig,
+
+    #[error("actual length is inconsistent relative to length indicator")]
+    InconsistentLength,
+
+    #[error("remainder bits were not zero")]
+    NonzeroTrailingPadding,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, GetSize, BFieldCodec)]
 #[cfg_attr(feature = "tasm-lib", derive(tasm_lib::prelude::TasmObject))]
 pub struct Chunk {
@@ -94,6 +120,168 @@ impl Chunk {
             relative_indices: sl.to_vec(),
         }
     }
+
+    /// Compresses a [`Chunk`] by encoding:
+    ///  - the length of the vector of relative indices as a u12
+    ///  - every element as a u12
+    ///  - the resulting bitvec as `Vec<u32>`.
+    pub fn pack(&self) -> Chunk {
+        if self.relative_indices.is_empty() {
+            return Self {
+                relative_indices: vec![],
+            };
+        }
+
+        // assert that we haven't already packed. I.e. that high bits are zero.
+        assert!(self.relative_indices.iter().all(|x| *x < CHUNK_SIZE));
+
+        assert!(
+            self.relative_indices.len() <= MAX_UNPACKED_LENGTH,
+            "Unpacked length of a chunk may not exceed {MAX_UNPACKED_LENGTH}"
+        );
+
+        let mut packed = vec![];
+        let mut width = 0_usize;
+        let mut current = 0_u64;
+        for &element in [self.relative_indices.len() as u32]
+            .iter()
+            .chain(&self.relative_indices)
+        {
+            width += 12;
+            current = (current << 12) | u64::from(element);
+
+            if width >= 32 {
+                let remainder = width % 32;
+                packed.push(
+                    u32::try_from(current >> remainder)
+                        .expect("width of `current` should always be less than 44"),
+                );
+                width -= 32;
+                current &= (1 << remainder) - 1;
+            }
+        }
+
+        if width != 0 {
+            packed.push(
+                u32::try_from(current << (32 - width))
+                    .expect("width of `current` should be less than 32 here"),
+            );
+        }
+
+        Self {
+            relative_indices: packed,
+        }
+    }
+
+    /// Inverse of [`Self::pack`].
+    pub fn try_unpack(&self) -> Result<Self, ChunkUnpackError> {
+        if self.relative_indices.is_empty() {
+            return Ok(Self {
+                relative_indices: vec![],
+            });
+        }
+
+        if self.relative_indices.len() > MAX_PACKED_LENGTH {
+            return Err(ChunkUnpackError::PayloadTooBig);
+        }
+
+        let mut unpacked = vec![];
+
+        let mut current = 0_u64;
+        let mut width = 0_usize;
+        let indicated_length = (self.relative_indices[0] >> 20) & ((1 << 12) - 1);
+
+        #[allow(clippy::manual_div_ceil, reason = "approach tasm implementation")]
+        let indicated_packed_length = ((indicated_length + 1) * 12 + 31) / 32;
+        if indicated_packed_length != u32::try_from(self.relative_indices.len()).unwrap() {
+            return Err(ChunkUnpackError::InconsistentLength);
+        }
+
+        let mut remaining_elements = indicated_length + 1;
+        // Invariant: number of elements left to iterate over is
+        // N == (remaining_elements * 12 - width + 31) / 32.
+        //
+        // Loop invariant before:
+        // N == self.relative_indices.len()
+        //   == indicated_packed_length
+        //               (as per above if-statement)
+        //   == ((indicated_length + 1) * 12 + 31) / 32
+        //               (by assignment above that)
+        //   == (remaining_elements * 12 + 31) / 32
+        //               (by assignment to remaining_elements)
+        //   == (remaining_elements * 12 - width + 31) / 32
+        //               (since width == 0).
+        for &element in &self.relative_indices {
+            current = (current << 32) | u64::from(element);
+            width += 32;
+
+            // At this point, width is guaranteed to be in [32;44). In every
+            // iteration of the next loop, 12 is subtracted. Therefore, the next
+            // loop can run for either 2 or 3 iterations -- tertium non datur.
+            while width >= 12 && remaining_elements != 0 {
+                let denominator = width / 12;
+                let remainder = width % 12;
+                let mask = (1 << 12) - 1;
+                unpacked.push(
+                    u32::try_from((current >> (remainder + (denominator - 1) * 12)) & mask)
+                        .expect("complicated invariant not satisfied"),
+                );
+                remaining_elements -= 1;
+                let mask = mask << (remainder + (denominator - 1) * 12);
+                let mask = !mask;
+                current &= mask;
+                width -= 12;
+            }
+
+            // Loop invariant at end of iteration: new number of elements left
+            // to iterate over N* = N - 1. Distinguish two cases.
+            //
+            //  1. Inner while-loop ran for 2 iterations.
+            //     width in [0;4) and width* = width + 8 (mod 12)
+            //                               = width + 8
+            //     remaining_elements* == remaining_elements - 2
+            //     N   == (remaining_elements * 12 + width + 31) / 32.
+            //     N* + 1 == ((remaining_elements* + 2) * 12 - (width* - 8) + 31) / 32
+            //     N* = (remaining_elements* * 12 + 24 - width* + 8 + 31 - 32) / 32
+            //        = (remaining_elements* * 12 - width + 31) / 32.
+            //
+            //  2. Inner while-loop ran for 3 iterations.
+            //     Then width is in [4;12) and width* = width + 8 (mod 12)
+            //                                        = width - 4
+            //     remaining_elements* == remaining_elements - 3
+            //     N   == (remaining_elements * 12 + width + 31) / 32.
+            //     N* + 1 == ((remaining_elements* + 3) * 12 - (width* + 4) + 31) / 32
+            //     N* = (remaining_elements* * 12 + 36 - width* -4 + 31 - 32) / 32
+            //        = (remaining_elements* * 12 - width + 31) / 32.
+            //
+            // So the invariant is restored.
+        }
+
+        // Loop invariant afterwards:
+        // N == 0
+        //   == (remaining_elements * 12 - width + 31) / 32, so
+        //   remaining_elements * 12 - width + 31 < 32
+        //   remaining_elements * 12 - width < 1
+        // From width in [0;12) it follows that remaining_elements == 0.
+        // So it is not necessary check that remaining_elements == 0.
+
+        let total_bit_length = (indicated_length + 1) * 12;
+        let num_non_padding_bits_in_last_element = total_bit_length % 32;
+        let tail_length = if num_non_padding_bits_in_last_element != 0 {
+            32 - num_non_padding_bits_in_last_element
+        } else {
+            0
+        };
+        let mask = (1 << tail_length) - 1;
+
+        if *self.relative_indices.last().unwrap() & mask != 0 {
+            return Err(ChunkUnpackError::NonzeroTrailingPadding);
+        }
+
+        Ok(Self {
+            relative_indices: unpacked[1..].to_vec(),
+        })
+    }
 }
 ///# [cfg (any (test , feature = "arbitrary-impls"))]
 #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
@@ -322,6 +510,106 @@ mod tests {
         println!("Pr[#elements in Chunk >= 4096] ≈ {overfull_probability:e}");
     }
 }
+#[cfg(test)]
+mod packing_tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn packing_empty_chunk() {
+        let chunk = Chunk::empty_chunk();
+        assert!(chunk.pack().relative_indices.is_empty());
+        assert!(
+            chunk
+                .pack()
+                .try_unpack()
+                .unwrap()
+                .relative_indices
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn pack_unpack_unit_6_zeros() {
+        let chunk = Chunk {
+            relative_indices: vec![0; 6],
+        };
+        let packed = chunk.pack();
+        let unpacked = packed.try_unpack().unwrap();
+        assert_eq!(chunk, unpacked);
+    }
+
+    #[test]
+    fn pack_unpack_unit_7_zeros() {
+        let chunk = Chunk {
+            relative_indices: vec![0; 7],
+        };
+        let packed = chunk.pack();
+        let unpacked = packed.try_unpack().unwrap();
+        assert_eq!(chunk, unpacked);
+    }
+    #[test]
+    fn pack_unpack_unit_10_nonzeros() {
+        let chunk = Chunk {
+            relative_indices: vec![392, 1192, 2453, 527, 2430, 2423, 257, 290, 2807, 122],
+        };
+        let packed = chunk.pack();
+        let unpacked = packed.try_unpack().unwrap();
+        assert_eq!(chunk, unpacked);
+    }
+
+    #[test]
+    fn packing_sparse_chunks() {
+        let mut rng = rand::rng();
+        for i in 0..20 {
+            let chunk = Chunk {
+                relative_indices: vec![rng.random_range(0..CHUNK_SIZE); i],
+            };
+            let packed = chunk.pack();
+            let unpacked = packed.try_unpack().unwrap();
+            assert_eq!(chunk, unpacked);
+        }
+    }
+
+    #[test]
+    fn can_trigger_error_payload_too_big() {
+        let packed_chunk = Chunk {
+            relative_indices: vec![1; MAX_PACKED_LENGTH + 1],
+        };
+        assert_eq!(
+            ChunkUnpackError::PayloadTooBig,
+            packed_chunk.try_unpack().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn can_trigger_error_inconsistent_length() {
+        let chunk = Chunk {
+            relative_indices: vec![392, 1192, 2453, 527, 2430, 2423, 257, 290, 2807, 122],
+        };
+        let mut packed = chunk.pack();
+        packed.relative_indices.push(0);
+        assert_eq!(
+            ChunkUnpackError::InconsistentLength,
+            packed.try_unpack().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn can_trigger_error_nonzero_trailing_padding() {
+        let chunk = Chunk {
+            relative_indices: vec![392, 1192, 2453, 527, 2430, 2423, 257, 290, 2807, 122],
+        };
+        let mut packed = chunk.pack();
+        *packed.relative_indices.last_mut().unwrap() |= 1;
+        assert_eq!(
+            ChunkUnpackError::NonzeroTrailingPadding,
+            packed.try_unpack().unwrap_err()
+        );
+    }
+}
+
 #[cfg(test)]
 #[allow(unused_imports)]
 #[allow(unused_variables)]
@@ -329,34 +617,88 @@ mod tests {
 #[allow(non_snake_case)]
 mod generated_tests {
     use super::*;
+    use crate::mutator_set::commit;
+    use crate::mutator_set::ms_membership_proof::MsMembershipProof;
+    use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
     use crate::test_shared::*;
     use bincode;
+    use serde::de::DeserializeOwned;
     use serde::{Deserialize, Serialize};
     pub mod nc {
-        //        pub use neptune_cash::util_types::mutator_set::chunk::Chunk;
+        pub use neptune_cash::util_types::mutator_set::commit;
+        pub use neptune_cash::util_types::mutator_set::ms_membership_proof::MsMembershipProof;
+        pub use neptune_cash::util_types::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
     }
+
+    /// Chunk is private in neptune-core, so take the chunks of a removal
+    /// record that both crates compute from the same mutator set operations.
+    fn chunks() -> Vec<(Chunk, impl Serialize + DeserializeOwned)> {
+        let mut msa = MutatorSetAccumulator::default();
+        let mut nc_msa = nc::MutatorSetAccumulator::default();
+        let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+        let mut mp = msa.prove(item, sender_randomness, receiver_preimage);
+        let mut nc_mp = nc_msa.prove(dg(item), dg(sender_randomness), dg(receiver_preimage));
+        msa.add(&commit(item, sender_randomness, receiver_preimage.hash()));
+        nc_msa.add(&nc::commit(
+            dg(item),
+            dg(sender_randomness),
+            dg(receiver_preimage.hash()),
+        ));
+
+        // slide the window until some of the item's indices live in inactive
+        // chunks
+        while mp.target_chunks.is_empty() {
+            let [other_item, sender_randomness, receiver_digest]: [Digest; 3] = rand::random();
+            let addition_record = commit(other_item, sender_randomness, receiver_digest);
+            let nc_addition_record =
+                nc::commit(dg(other_item), dg(sender_randomness), dg(receiver_digest));
+            MsMembershipProof::batch_update_from_addition(
+                &mut [&mut mp],
+                &[item],
+                &msa,
+                &addition_record,
+            )
+            .unwrap();
+            nc::MsMembershipProof::batch_update_from_addition(
+                &mut [&mut nc_mp],
+                &[dg(item)],
+                &nc_msa,
+                &nc_addition_record,
+            )
+            .unwrap();
+            msa.add(&addition_record);
+            nc_msa.add(&nc_addition_record);
+        }
+
+        let chunks = msa.drop(item, &mp).target_chunks.indices_and_chunks();
+        let nc_chunks = nc_msa
+            .drop(dg(item), &nc_mp)
+            .target_chunks
+            .indices_and_chunks();
+        assert_eq!(chunks.len(), nc_chunks.len());
+        chunks
+            .into_iter()
+            .zip(nc_chunks)
+            .map(|((_, chunk), (_, nc_chunk))| (chunk, nc_chunk))
+            .collect()
+    }
+
     #[test]
     fn test_bincode_serialization_for_chunk() {
-        todo!("Chunk is now private in neptune-core.  maybe make it public again?");
-        //        let original_instance: Chunk = todo!("Instantiate");
-        //        let nc_instance: nc::Chunk = todo!("Instantiate");
-        //        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+        for (original_instance, nc_instance) in chunks() {
+            test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+        }
     }
     #[test]
     fn test_serde_json_serialization_for_chunk() {
-        todo!("Chunk is now private in neptune-core.  maybe make it public again?");
-        //        let original_instance: Chunk = todo!("Instantiate");
-        //        let nc_instance: nc::Chunk = todo!("Instantiate");
-        //        test_serde_json_serialization_for_type(original_instance, Some(nc_instance));
+        for (original_instance, nc_instance) in chunks() {
+            test_serde_json_serialization_for_type(original_instance, Some(nc_instance));
+        }
     }
     #[test]
     fn test_serde_json_wasm_serialization_for_chunk() {
-        todo!("Chunk is now private in neptune-core.  maybe make it public again?");
-        //        let original_instance: Chunk = todo!("Instantiate");
-        //        let nc_instance: nc::Chunk = todo!("Instantiate");
-        //        test_serde_json_wasm_serialization_for_type(
-        //            original_instance,
-        //            Some(nc_instance),
-        //        );
+        for (original_instance, nc_instance) in chunks() {
+            test_serde_json_wasm_serialization_for_type(original_instance, Some(nc_instance));
+        }
     }
 }
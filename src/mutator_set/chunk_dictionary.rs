@@ -17,7 +17,11 @@ type ChunkIndex = u64;
     derive(arbitrary::Arbitrary)
 )]
 pub struct ChunkDictionary {
-    dictionary: Vec<(u64, (MmrMembershipProof, Chunk))>,
+    // {chunk index => (MMR membership proof for the whole chunk to which index belongs, chunk value)}
+    // This list is always sorted. It has max. NUM_TRIALS=45 elements, so we
+    // don't care about the cost of reallocation when `insert`ing or
+    // `remove`ing.
+    pub(crate) dictionary: Vec<(u64, (MmrMembershipProof, Chunk))>,
 }
 
 impl ChunkDictionary {
@@ -1,8 +1,47 @@
 pub mod active_window;
 pub mod addition_record;
+pub mod archival_mmr;
+pub mod archival_mutator_set;
 pub mod chunk;
 pub mod chunk_dictionary;
 pub mod ms_membership_proof;
 pub mod mutator_set_accumulator;
 pub mod removal_record;
 mod shared;
+
+use addition_record::AdditionRecord;
+use thiserror::Error;
+use twenty_first::prelude::Digest;
+use twenty_first::prelude::Tip5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum MutatorSetError {
+    #[error(
+        "removal index refers to chunk {saw_chunk_index} which lies beyond the active window (max chunk index {current_max_chunk_index})"
+    )]
+    AbsoluteRemovalIndexIsFutureIndex {
+        current_max_chunk_index: u64,
+        saw_chunk_index: u64,
+    },
+
+    #[error("requested AOCL leaf {leaf_index} but the AOCL only has {leaf_count} leafs")]
+    RequestedAoclAuthPathOutOfBounds { leaf_index: u64, leaf_count: u64 },
+
+    #[error("item does not commit to the AOCL leaf at index {aocl_leaf_index}")]
+    AoclLeafMismatch { aocl_leaf_index: u64 },
+}
+
+/// Get the canonical commitment for an item, i.e., the leaf that goes into
+/// the AOCL when the item is added to the mutator set.
+pub fn commit(item: Digest, sender_randomness: Digest, receiver_digest: Digest) -> AdditionRecord {
+    let canonical_commitment =
+        Tip5::hash_pair(Tip5::hash_pair(item, sender_randomness), receiver_digest);
+
+    AdditionRecord::new(canonical_commitment)
+}
+
+/// Return the number of leafs in the inactive part of the SWBF, given the
+/// number of leafs in the AOCL.
+pub fn aocl_to_swbfi_leaf_counts(aocl_leaf_count: u64) -> u64 {
+    aocl_leaf_count.saturating_sub(1) / u64::from(shared::BATCH_SIZE)
+}
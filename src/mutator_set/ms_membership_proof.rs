@@ -1,10 +1,23 @@
+use super::addition_record::AdditionRecord;
 use super::chunk_dictionary::ChunkDictionary;
+use super::commit;
+use super::mutator_set_accumulator::MutatorSetAccumulator;
+use super::removal_record::RemovalRecord;
+use super::removal_record::absolute_index_set::AbsoluteIndexSet;
+use super::shared::BATCH_SIZE;
+use super::shared::CHUNK_SIZE;
+use super::shared::get_batch_mutation_argument_for_removal_record;
 use get_size2::GetSize;
+use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt;
 use twenty_first::prelude::*;
+use twenty_first::util_types::mmr::mmr_trait::LeafMutation;
+use twenty_first::util_types::mmr::mmr_trait::Mmr;
 impl Error for MembershipProofError {}
 
 impl fmt::Display for MembershipProofError {
@@ -31,6 +44,444 @@ pub struct MsMembershipProof {
     pub aocl_leaf_index: u64,
     pub target_chunks: ChunkDictionary,
 }
+
+impl MsMembershipProof {
+    /// Compute the indices that will be added to the SWBF if this item is removed.
+    pub fn compute_indices(&self, item: Digest) -> AbsoluteIndexSet {
+        AbsoluteIndexSet::compute(
+            item,
+            self.sender_randomness,
+            self.receiver_preimage,
+            self.aocl_leaf_index,
+        )
+    }
+
+    /// The addition record that added the item this membership proof is for.
+    pub fn addition_record(&self, item: Digest) -> AdditionRecord {
+        commit(item, self.sender_randomness, self.receiver_preimage.hash())
+    }
+
+    /// Update a list of membership proofs in anticipation of an addition to
+    /// the given mutator set. Returns the indices of the membership proofs
+    /// that were modified.
+    ///
+    /// # Panics
+    ///
+    ///  - If the number of membership proofs and items differ.
+    ///  - If any of the membership proofs is for an item that has not yet
+    ///    been added to the mutator set.
+    pub fn batch_update_from_addition(
+        membership_proofs: &mut [&mut Self],
+        own_items: &[Digest],
+        mutator_set: &MutatorSetAccumulator,
+        addition_record: &AdditionRecord,
+    ) -> Result<Vec<usize>, MembershipProofError> {
+        assert_eq!(
+            membership_proofs.len(),
+            own_items.len(),
+            "Function must be called with same number of membership proofs and items"
+        );
+        assert!(
+            membership_proofs
+                .iter()
+                .all(|mp| mp.aocl_leaf_index < mutator_set.aocl.num_leafs()),
+            "No AOCL leaf index can point outside of provided mutator set. AOCL leaf count: {}",
+            mutator_set.aocl.num_leafs()
+        );
+
+        let new_item_index = mutator_set.aocl.num_leafs();
+
+        // update the AOCL MMR membership proofs
+        let aocl_leaf_indices = membership_proofs
+            .iter()
+            .map(|mp| mp.aocl_leaf_index)
+            .collect_vec();
+        let mut aocl_mps = membership_proofs
+            .iter_mut()
+            .map(|mp| &mut mp.auth_path_aocl)
+            .collect_vec();
+        let updated_by_aocl = MmrMembershipProof::batch_update_from_append(
+            &mut aocl_mps,
+            &aocl_leaf_indices,
+            new_item_index,
+            addition_record.canonical_commitment,
+            &mutator_set.aocl.peaks(),
+        );
+
+        // if the window does not slide, we are done
+        if !MutatorSetAccumulator::window_slides(new_item_index) {
+            return Ok(updated_by_aocl);
+        }
+
+        // The window slides, so the first chunk of the active window becomes
+        // the newest leaf of the SWBF MMR. It's important to use an MMR
+        // accumulator here, since the inactive part of an archival mutator set
+        // can be huge.
+        let new_chunk = mutator_set.swbf_active.slid_chunk();
+        let new_chunk_digest = Tip5::hash(&new_chunk);
+        let new_chunk_index = mutator_set.swbf_inactive.num_leafs();
+        debug_assert_eq!(
+            new_item_index / u64::from(BATCH_SIZE) - 1,
+            new_chunk_index,
+            "Number of SWBF MMR leafs must match current batch index"
+        );
+        let mut swbf_inactive = mutator_set.swbf_inactive.to_accumulator();
+        let new_chunk_auth_path = swbf_inactive.append(new_chunk_digest);
+
+        // Find the membership proofs whose indices live in the new chunk, and
+        // those that hold proofs for chunks that are already inactive.
+        let mut mps_for_new_chunk = vec![];
+        let mut mps_with_inactive_chunks = HashSet::new();
+        for (i, (mp, item)) in membership_proofs.iter().zip_eq(own_items).enumerate() {
+            let chunk_indices: HashSet<u64> = mp
+                .compute_indices(*item)
+                .to_array()
+                .into_iter()
+                .map(|index| (index / u128::from(CHUNK_SIZE)) as u64)
+                .collect();
+            if chunk_indices.contains(&new_chunk_index) {
+                mps_for_new_chunk.push(i);
+            }
+            if chunk_indices.iter().any(|&ci| ci < new_chunk_index) {
+                mps_with_inactive_chunks.insert(i);
+            }
+        }
+
+        // insert the new chunk into the chunk dictionaries that need it
+        for &i in &mps_for_new_chunk {
+            let previous = membership_proofs[i].target_chunks.insert(
+                new_chunk_index,
+                (new_chunk_auth_path.clone(), new_chunk.clone()),
+            );
+            if previous.is_some() {
+                return Err(MembershipProofError::AlreadyExistingChunk(new_chunk_index));
+            }
+        }
+
+        // update the MMR membership proofs of the chunks that were already
+        // inactive
+        let mut swbf_mps = vec![];
+        let mut swbf_leaf_indices = vec![];
+        let mut swbf_mp_owners = vec![];
+        for (i, mp) in membership_proofs.iter_mut().enumerate() {
+            if !mps_with_inactive_chunks.contains(&i) {
+                continue;
+            }
+            for (chunk_index, (mmr_mp, _chunk)) in mp.target_chunks.iter_mut() {
+                if *chunk_index != new_chunk_index {
+                    swbf_mps.push(mmr_mp);
+                    swbf_leaf_indices.push(*chunk_index);
+                    swbf_mp_owners.push(i);
+                }
+            }
+        }
+        let updated_swbf_mps = MmrMembershipProof::batch_update_from_append(
+            &mut swbf_mps,
+            &swbf_leaf_indices,
+            new_chunk_index,
+            new_chunk_digest,
+            &mutator_set.swbf_inactive.peaks(),
+        );
+
+        let updated = updated_by_aocl
+            .into_iter()
+            .chain(mps_for_new_chunk)
+            .chain(updated_swbf_mps.into_iter().map(|j| swbf_mp_owners[j]))
+            .sorted()
+            .dedup()
+            .collect_vec();
+
+        Ok(updated)
+    }
+
+    /// Revert the effect of (possibly several) additions to the mutator set,
+    /// such that the membership proof becomes valid relative to the given,
+    /// earlier mutator set.
+    ///
+    /// # Panics
+    ///
+    ///  - If the item was not yet added to the earlier mutator set.
+    pub fn revert_update_from_batch_addition(
+        &mut self,
+        previous_mutator_set: &MutatorSetAccumulator,
+    ) {
+        let previous_aocl_leaf_count = previous_mutator_set.aocl.num_leafs();
+        assert!(
+            self.aocl_leaf_index < previous_aocl_leaf_count,
+            "Cannot revert a membership proof to a state before the item was added to the mutator set"
+        );
+
+        // The authentication path of a leaf in an MMR only ever grows on
+        // appends, so reverting amounts to truncating it to the height of the
+        // Merkle tree that held the leaf back then.
+        let aocl_mp_length = (self.aocl_leaf_index ^ previous_aocl_leaf_count).ilog2() as usize;
+        self.auth_path_aocl
+            .authentication_path
+            .truncate(aocl_mp_length);
+
+        // chunks that were not yet inactive must go
+        let previous_swbfi_leaf_count = previous_mutator_set.swbf_inactive.num_leafs();
+        self.target_chunks
+            .retain(|(chunk_index, _)| *chunk_index < previous_swbfi_leaf_count);
+
+        for (chunk_index, (mmr_mp, _chunk)) in self.target_chunks.iter_mut() {
+            let swbf_mp_length = (*chunk_index ^ previous_swbfi_leaf_count).ilog2() as usize;
+            mmr_mp.authentication_path.truncate(swbf_mp_length);
+        }
+    }
+
+    /// Update a list of membership proofs in anticipation of a removal from
+    /// the mutator set. Returns the indices of the membership proofs that were
+    /// modified.
+    pub fn batch_update_from_remove(
+        membership_proofs: &mut [&mut Self],
+        removal_record: &RemovalRecord,
+    ) -> Result<Vec<usize>, MembershipProofError> {
+        let mut chunk_dictionaries = membership_proofs
+            .iter_mut()
+            .map(|mp| &mut mp.target_chunks)
+            .collect_vec();
+        let (mutated_chunk_dictionaries, mutation_argument) =
+            get_batch_mutation_argument_for_removal_record(removal_record, &mut chunk_dictionaries);
+
+        let updated_swbf_mp_owners =
+            Self::batch_update_swbf_mps(&mut chunk_dictionaries, mutation_argument);
+
+        let updated = mutated_chunk_dictionaries
+            .into_iter()
+            .chain(updated_swbf_mp_owners)
+            .sorted()
+            .dedup()
+            .collect_vec();
+
+        Ok(updated)
+    }
+
+    /// Revert the effect of a removal record on this membership proof, such
+    /// that it becomes valid relative to the mutator set from before the
+    /// removal record was applied.
+    ///
+    /// The removal record must have been synced to the mutator set it was
+    /// applied to, which means its chunks hold the values from before the
+    /// removal.
+    pub fn revert_update_from_remove(&mut self, removal_record: &RemovalRecord) {
+        let mut previous_chunks: HashMap<u64, _> = HashMap::new();
+        for (chunk_index, (_mmr_mp, chunk)) in removal_record.target_chunks.iter() {
+            previous_chunks.insert(*chunk_index, chunk);
+        }
+
+        for (chunk_index, (_mmr_mp, chunk)) in self.target_chunks.iter_mut() {
+            if let Some(previous_chunk) = previous_chunks.get(chunk_index) {
+                *chunk = (*previous_chunk).clone();
+            }
+        }
+
+        let mutation_argument = removal_record
+            .target_chunks
+            .iter()
+            .map(|(chunk_index, (mmr_mp, chunk))| (*chunk_index, mmr_mp.clone(), Tip5::hash(chunk)))
+            .collect_vec();
+        Self::batch_update_swbf_mps(&mut [&mut self.target_chunks], mutation_argument);
+    }
+
+    /// Apply leaf mutations of the SWBF MMR to all MMR membership proofs in
+    /// the given chunk dictionaries. Returns the indices of the chunk
+    /// dictionaries that were modified.
+    fn batch_update_swbf_mps(
+        chunk_dictionaries: &mut [&mut ChunkDictionary],
+        mutation_argument: Vec<(u64, MmrMembershipProof, Digest)>,
+    ) -> Vec<usize> {
+        let mut swbf_mps = vec![];
+        let mut swbf_leaf_indices = vec![];
+        let mut swbf_mp_owners = vec![];
+        for (i, chunk_dictionary) in chunk_dictionaries.iter_mut().enumerate() {
+            for (chunk_index, (mmr_mp, _chunk)) in chunk_dictionary.iter_mut() {
+                swbf_mps.push(mmr_mp);
+                swbf_leaf_indices.push(*chunk_index);
+                swbf_mp_owners.push(i);
+            }
+        }
+
+        let leaf_mutations = mutation_argument
+            .into_iter()
+            .map(|(chunk_index, mmr_mp, new_leaf)| LeafMutation::new(chunk_index, new_leaf, mmr_mp))
+            .collect_vec();
+        MmrMembershipProof::batch_update_from_batch_leaf_mutation(
+            &mut swbf_mps,
+            &swbf_leaf_indices,
+            leaf_mutations,
+        )
+        .into_iter()
+        .map(|j| swbf_mp_owners[j])
+        .collect()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod nc {
+        pub use neptune_cash::util_types::mutator_set::ms_membership_proof::MsMembershipProof;
+        pub use neptune_cash::util_types::mutator_set::removal_record::RemovalRecord;
+    }
+
+    fn convert<T: Serialize, U: serde::de::DeserializeOwned>(value: &T) -> U {
+        bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap()
+    }
+
+    fn add_and_update(
+        msa: &mut MutatorSetAccumulator,
+        items_and_proofs: &mut Vec<(Digest, MsMembershipProof)>,
+    ) {
+        let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+        let addition_record = commit(item, sender_randomness, receiver_preimage.hash());
+        let membership_proof = msa.prove(item, sender_randomness, receiver_preimage);
+
+        let (items, mut proofs): (Vec<_>, Vec<_>) = items_and_proofs.drain(..).unzip();
+        MsMembershipProof::batch_update_from_addition(
+            &mut proofs.iter_mut().collect_vec(),
+            &items,
+            msa,
+            &addition_record,
+        )
+        .unwrap();
+        msa.add(&addition_record);
+
+        items_and_proofs.extend(items.into_iter().zip(proofs));
+        items_and_proofs.push((item, membership_proof));
+    }
+
+    #[test]
+    fn membership_proofs_stay_valid_across_additions_and_removals() {
+        let mut msa = MutatorSetAccumulator::default();
+        let mut items_and_proofs = vec![];
+        for _ in 0..40 * BATCH_SIZE {
+            add_and_update(&mut msa, &mut items_and_proofs);
+        }
+        for (item, mp) in &items_and_proofs {
+            assert!(msa.verify(*item, mp));
+        }
+
+        // remove the oldest item, whose indices mostly live in inactive chunks
+        let (removed_item, removed_mp) = items_and_proofs.remove(0);
+        let removal_record = msa.drop(removed_item, &removed_mp);
+        let (items, mut proofs): (Vec<_>, Vec<_>) = items_and_proofs.into_iter().unzip();
+        let msa_before_removal = msa.clone();
+        let proofs_before_removal = proofs.clone();
+
+        MsMembershipProof::batch_update_from_remove(
+            &mut proofs.iter_mut().collect_vec(),
+            &removal_record,
+        )
+        .unwrap();
+        msa.remove(&removal_record);
+
+        assert!(!msa.verify(removed_item, &removed_mp));
+        for (item, mp) in items.iter().zip(&proofs) {
+            assert!(msa.verify(*item, mp));
+        }
+
+        // reverting the removal restores the proofs to their previous state
+        for mp in &mut proofs {
+            mp.revert_update_from_remove(&removal_record);
+        }
+        assert_eq!(proofs_before_removal, proofs);
+        for (item, mp) in items.iter().zip(&proofs) {
+            assert!(msa_before_removal.verify(*item, mp));
+        }
+    }
+
+    #[test]
+    fn batch_update_from_remove_reports_only_modified_proofs() {
+        let mut msa = MutatorSetAccumulator::default();
+        let mut items_and_proofs = vec![];
+        for _ in 0..40 * BATCH_SIZE {
+            add_and_update(&mut msa, &mut items_and_proofs);
+        }
+
+        let (removed_item, removed_mp) = items_and_proofs.remove(0);
+        let removal_record = msa.drop(removed_item, &removed_mp);
+        let mut proofs = items_and_proofs.into_iter().map(|(_, mp)| mp).collect_vec();
+        let proofs_before_removal = proofs.clone();
+
+        let modified = MsMembershipProof::batch_update_from_remove(
+            &mut proofs.iter_mut().collect_vec(),
+            &removal_record,
+        )
+        .unwrap();
+        for (i, (before, after)) in proofs_before_removal.iter().zip(&proofs).enumerate() {
+            assert_eq!(before != after, modified.contains(&i));
+        }
+
+        // the proofs are already up to date, so nothing changes a second time
+        let modified_again = MsMembershipProof::batch_update_from_remove(
+            &mut proofs.iter_mut().collect_vec(),
+            &removal_record,
+        )
+        .unwrap();
+        assert!(modified_again.is_empty());
+    }
+
+    #[test]
+    fn batch_update_from_remove_agrees_with_neptune_cash() {
+        let mut msa = MutatorSetAccumulator::default();
+        let mut items_and_proofs = vec![];
+        for _ in 0..40 * BATCH_SIZE {
+            add_and_update(&mut msa, &mut items_and_proofs);
+        }
+
+        let (removed_item, removed_mp) = items_and_proofs.remove(0);
+        let removal_record = msa.drop(removed_item, &removed_mp);
+        let nc_removal_record: nc::RemovalRecord = convert(&removal_record);
+        let mut proofs = items_and_proofs.into_iter().map(|(_, mp)| mp).collect_vec();
+        let mut nc_proofs: Vec<nc::MsMembershipProof> = proofs.iter().map(convert).collect();
+
+        let modified = MsMembershipProof::batch_update_from_remove(
+            &mut proofs.iter_mut().collect_vec(),
+            &removal_record,
+        )
+        .unwrap();
+        let nc_modified = nc::MsMembershipProof::batch_update_from_remove(
+            &mut nc_proofs.iter_mut().collect_vec(),
+            &nc_removal_record,
+        )
+        .unwrap();
+
+        assert_eq!(
+            nc_modified.into_iter().sorted().collect_vec(),
+            modified.into_iter().sorted().collect_vec()
+        );
+        for (mp, nc_mp) in proofs.iter().zip(&nc_proofs) {
+            assert_eq!(
+                bincode::serialize(nc_mp).unwrap(),
+                bincode::serialize(mp).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn revert_update_from_batch_addition_restores_earlier_proof() {
+        let mut msa = MutatorSetAccumulator::default();
+        let mut items_and_proofs = vec![];
+        for _ in 0..3 * BATCH_SIZE + 2 {
+            add_and_update(&mut msa, &mut items_and_proofs);
+        }
+        let msa_snapshot = msa.clone();
+        let proofs_snapshot = items_and_proofs.clone();
+
+        for _ in 0..5 * BATCH_SIZE + 3 {
+            add_and_update(&mut msa, &mut items_and_proofs);
+        }
+
+        for ((item, mut mp), (_, mp_snapshot)) in items_and_proofs.into_iter().zip(proofs_snapshot)
+        {
+            mp.revert_update_from_batch_addition(&msa_snapshot);
+            assert_eq!(mp_snapshot, mp);
+            assert!(msa_snapshot.verify(item, &mp));
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(unused_imports)]
 #[allow(unused_variables)]
@@ -1,9 +1,22 @@
 use super::active_window::ActiveWindow;
+use super::addition_record::AdditionRecord;
+use super::aocl_to_swbfi_leaf_counts;
+use super::chunk::Chunk;
+use super::commit;
+use super::ms_membership_proof::MsMembershipProof;
+use super::removal_record::RemovalRecord;
+use super::shared::BATCH_SIZE;
+use super::shared::CHUNK_SIZE;
+use super::shared::WINDOW_SIZE;
 use get_size2::GetSize;
+use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
 use twenty_first::prelude::*;
 use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+use twenty_first::util_types::mmr::mmr_trait::LeafMutation;
+use twenty_first::util_types::mmr::mmr_trait::Mmr;
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, GetSize, BFieldCodec)]
 ///# [cfg_attr (any (test , feature = "arbitrary-impls") , derive (arbitrary :: Arbitrary))]
 #[cfg_attr(
@@ -26,6 +39,320 @@ impl Default for MutatorSetAccumulator {
         }
     }
 }
+
+impl MutatorSetAccumulator {
+    pub fn new(
+        aocl: &[Digest],
+        aocl_leaf_count: u64,
+        swbf_inactive: &[Digest],
+        swbf_active: &ActiveWindow,
+    ) -> Self {
+        let swbf_inactive_leaf_count = aocl_to_swbfi_leaf_counts(aocl_leaf_count);
+        Self {
+            aocl: MmrAccumulator::init(aocl.to_vec(), aocl_leaf_count),
+            swbf_inactive: MmrAccumulator::init(swbf_inactive.to_vec(), swbf_inactive_leaf_count),
+            swbf_active: swbf_active.clone(),
+        }
+    }
+
+    /// Return the interval of chunk indices covered by the active window.
+    pub fn active_window_chunk_interval(&self) -> (u64, u64) {
+        let batch_index = self.get_batch_index();
+        (
+            batch_index,
+            batch_index + u64::from(WINDOW_SIZE / CHUNK_SIZE),
+        )
+    }
+
+    /// Return the batch index for the latest addition to the mutator set.
+    pub fn get_batch_index(&self) -> u64 {
+        match self.aocl.num_leafs() {
+            0 => 0,
+            n => (n - 1) / u64::from(BATCH_SIZE),
+        }
+    }
+
+    /// Determine if the window slides before absorbing an item, given the
+    /// index of the to-be-added item.
+    pub fn window_slides(added_index: u64) -> bool {
+        added_index != 0 && added_index.is_multiple_of(u64::from(BATCH_SIZE))
+    }
+
+    /// Determine if the window slides back when removing the item with the
+    /// given index from the AOCL.
+    pub fn window_slides_back(removed_index: u64) -> bool {
+        Self::window_slides(removed_index)
+    }
+
+    /// Like [`Self::add`] but also returns the chunk that was added to the
+    /// inactive part of the SWBF if the window slid, together with its index
+    /// in the SWBF MMR.
+    pub(crate) fn add_helper(&mut self, addition_record: &AdditionRecord) -> Option<(u64, Chunk)> {
+        let item_index = self.aocl.num_leafs();
+        self.aocl.append(addition_record.canonical_commitment);
+
+        if !Self::window_slides(item_index) {
+            return None;
+        }
+
+        // the first chunk of the active window becomes the newest leaf of the
+        // inactive part, then the window moves one chunk to the right
+        let new_chunk: Chunk = self.swbf_active.slid_chunk();
+        let chunk_digest: Digest = Tip5::hash(&new_chunk);
+        let new_chunk_index = self.swbf_inactive.num_leafs();
+        self.swbf_inactive.append(chunk_digest);
+        self.swbf_active.slide_window();
+
+        Some((new_chunk_index, new_chunk))
+    }
+
+    /// Apply a removal record and return the chunks of the inactive part of
+    /// the SWBF that were modified in the process, keyed by chunk index.
+    ///
+    /// # Panics
+    ///
+    ///  - If the removal record is missing a chunk that it needs to modify.
+    pub(crate) fn remove_helper(&mut self, removal_record: &RemovalRecord) -> HashMap<u64, Chunk> {
+        let batch_index = self.get_batch_index();
+        let active_window_start = u128::from(batch_index) * u128::from(CHUNK_SIZE);
+
+        let mut new_target_chunks = removal_record.target_chunks.clone();
+        let chunkindices_to_indices_dict = removal_record.get_chunkidx_to_indices_dict();
+
+        for (chunk_index, indices) in chunkindices_to_indices_dict {
+            if chunk_index >= batch_index {
+                for index in indices {
+                    let relative_index = (index - active_window_start) as u32;
+                    self.swbf_active.insert(relative_index);
+                }
+
+                continue;
+            }
+
+            let (_mmr_mp, relevant_chunk) =
+                new_target_chunks.get_mut(&chunk_index).unwrap_or_else(|| {
+                    panic!(
+                        "Can't get chunk index {chunk_index} from removal record dictionary.\n\
+                        AOCL size: {}\nbatch index: {batch_index}",
+                        self.aocl.num_leafs(),
+                    )
+                });
+            for index in indices {
+                let relative_index = (index % u128::from(CHUNK_SIZE)) as u32;
+                relevant_chunk.insert(relative_index);
+            }
+        }
+
+        let leaf_mutations = new_target_chunks
+            .chunk_indices_and_membership_proofs_and_leafs()
+            .into_iter()
+            .map(|(chunk_index, mmr_mp, leaf)| LeafMutation::new(chunk_index, leaf, mmr_mp))
+            .collect_vec();
+        self.swbf_inactive
+            .batch_mutate_leaf_and_update_mps(&mut [], &[], leaf_mutations);
+
+        new_target_chunks
+            .into_iter()
+            .map(|(chunk_index, (_mmr_mp, chunk))| (chunk_index, chunk))
+            .collect()
+    }
+
+    /// Generate a membership proof for an item that is about to be added to
+    /// the mutator set, i.e., the proof becomes valid once the corresponding
+    /// addition record is applied.
+    pub fn prove(
+        &self,
+        item: Digest,
+        sender_randomness: Digest,
+        receiver_preimage: Digest,
+    ) -> MsMembershipProof {
+        let canonical_commitment =
+            commit(item, sender_randomness, receiver_preimage.hash()).canonical_commitment;
+        let auth_path_aocl = self.aocl.to_accumulator().append(canonical_commitment);
+
+        MsMembershipProof {
+            sender_randomness,
+            receiver_preimage,
+            auth_path_aocl,
+            aocl_leaf_index: self.aocl.num_leafs(),
+            target_chunks: Default::default(),
+        }
+    }
+
+    /// Check if an item is a member of the mutator set.
+    pub fn verify(&self, item: Digest, membership_proof: &MsMembershipProof) -> bool {
+        // If the AOCL does not contain the leaf index, the item cannot be a
+        // member. This also guarantees that the indices derived below are not
+        // "future" indices.
+        if self.aocl.num_leafs() <= membership_proof.aocl_leaf_index {
+            return false;
+        }
+
+        let leaf = membership_proof.addition_record(item).canonical_commitment;
+        let is_aocl_member = membership_proof.auth_path_aocl.verify(
+            membership_proof.aocl_leaf_index,
+            leaf,
+            &self.aocl.peaks(),
+            self.aocl.num_leafs(),
+        );
+        if !is_aocl_member {
+            return false;
+        }
+
+        let all_indices = membership_proof.compute_indices(item);
+        let Ok((indices_in_inactive_swbf, indices_in_active_swbf)) =
+            all_indices.split_by_activity(self)
+        else {
+            return false;
+        };
+
+        // Every chunk in the inactive part that the indices point into must be
+        // authenticated by the membership proof.
+        let swbfi_peaks = self.swbf_inactive.peaks();
+        let swbfi_leaf_count = self.swbf_inactive.num_leafs();
+        let mut has_absent_index = false;
+        for (chunk_index, indices) in indices_in_inactive_swbf {
+            let Some((mmr_mp, chunk)) = membership_proof.target_chunks.get(&chunk_index) else {
+                return false;
+            };
+
+            if !mmr_mp.verify(
+                chunk_index,
+                Tip5::hash(chunk),
+                &swbfi_peaks,
+                swbfi_leaf_count,
+            ) {
+                return false;
+            }
+
+            has_absent_index |= indices
+                .into_iter()
+                .any(|index| !chunk.contains((index % u128::from(CHUNK_SIZE)) as u32));
+        }
+
+        let window_start = u128::from(self.get_batch_index()) * u128::from(CHUNK_SIZE);
+        has_absent_index |= indices_in_active_swbf
+            .into_iter()
+            .any(|index| !self.swbf_active.contains((index - window_start) as u32));
+
+        has_absent_index
+    }
+
+    /// Generate a removal record with which to update the set commitment.
+    pub fn drop(&self, item: Digest, membership_proof: &MsMembershipProof) -> RemovalRecord {
+        RemovalRecord {
+            absolute_indices: membership_proof.compute_indices(item),
+            target_chunks: membership_proof.target_chunks.clone(),
+        }
+    }
+
+    /// Add an item to the mutator set.
+    ///
+    /// Note that this function cannot return a membership proof, as it does
+    /// not know the randomness used to produce the commitment.
+    pub fn add(&mut self, addition_record: &AdditionRecord) {
+        self.add_helper(addition_record);
+    }
+
+    /// Remove an item from the mutator set.
+    pub fn remove(&mut self, removal_record: &RemovalRecord) {
+        self.remove_helper(removal_record);
+    }
+
+    /// Check if a removal record can be applied to the mutator set. Returns
+    /// false if the removal record's MMR membership proofs are not synced, or
+    /// if all of its indices are already set.
+    pub fn can_remove(&self, removal_record: &RemovalRecord) -> bool {
+        if !removal_record.validate(self) {
+            return false;
+        }
+
+        let active_window_start = u128::from(self.get_batch_index()) * u128::from(CHUNK_SIZE);
+        removal_record
+            .absolute_indices
+            .to_array()
+            .into_iter()
+            .any(|index| {
+                if index < active_window_start {
+                    let chunk_index = (index / u128::from(CHUNK_SIZE)) as u64;
+                    let relative_index = (index % u128::from(CHUNK_SIZE)) as u32;
+                    removal_record
+                        .target_chunks
+                        .get(&chunk_index)
+                        .is_some_and(|(_mmr_mp, chunk)| !chunk.contains(relative_index))
+                } else {
+                    let relative_index = (index - active_window_start) as u32;
+                    !self.swbf_active.contains(relative_index)
+                }
+            })
+    }
+
+    /// Commit to the mutator set as a whole.
+    pub fn hash(&self) -> Digest {
+        let aocl_mmr_bagged = self.aocl.bag_peaks();
+        let inactive_swbf_bagged = self.swbf_inactive.bag_peaks();
+        let active_swbf_bagged = Tip5::hash(&self.swbf_active);
+        let default = Digest::default();
+
+        Tip5::hash_pair(
+            Tip5::hash_pair(aocl_mmr_bagged, inactive_swbf_bagged),
+            Tip5::hash_pair(active_swbf_bagged, default),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_shared::dg;
+
+    mod nc {
+        pub use neptune_cash::util_types::mutator_set::commit;
+        pub use neptune_cash::util_types::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+    }
+
+    fn to_nc(msa: &MutatorSetAccumulator) -> nc::MutatorSetAccumulator {
+        bincode::deserialize(&bincode::serialize(msa).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn commit_agrees_with_neptune_cash() {
+        let [item, sender_randomness, receiver_digest]: [Digest; 3] = rand::random();
+        let ours = commit(item, sender_randomness, receiver_digest);
+        let theirs = nc::commit(dg(item), dg(sender_randomness), dg(receiver_digest));
+        assert_eq!(dg(ours.canonical_commitment), theirs.canonical_commitment);
+    }
+
+    #[test]
+    fn add_prove_verify_remove_agrees_with_neptune_cash() {
+        let mut msa = MutatorSetAccumulator::default();
+        let mut items_and_proofs = vec![];
+
+        // add enough items to slide the window a couple of times
+        for _ in 0..3 * BATCH_SIZE + 1 {
+            let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+            let addition_record = commit(item, sender_randomness, receiver_preimage.hash());
+            let membership_proof = msa.prove(item, sender_randomness, receiver_preimage);
+            assert!(!msa.verify(item, &membership_proof));
+
+            msa.add(&addition_record);
+            assert_eq!(to_nc(&msa).hash(), dg(msa.hash()));
+            assert!(msa.verify(item, &membership_proof));
+            items_and_proofs.push((item, membership_proof));
+        }
+
+        // items added in the last batch only touch the active window
+        let (item, membership_proof) = items_and_proofs.last().unwrap();
+        let removal_record = msa.drop(*item, membership_proof);
+        assert!(msa.can_remove(&removal_record));
+
+        msa.remove(&removal_record);
+        assert_eq!(to_nc(&msa).hash(), dg(msa.hash()));
+        assert!(!msa.verify(*item, membership_proof));
+        assert!(!msa.can_remove(&removal_record));
+    }
+}
+
 #[cfg(test)]
 #[allow(unused_imports)]
 #[allow(unused_variables)]
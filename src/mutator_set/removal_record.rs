@@ -1,14 +1,13 @@
 pub mod absolute_index_set;
-// pub(crate) mod chunk;
-// pub(crate) mod chunk_dictionary;
-// pub(crate) mod removal_record_list;
+pub mod removal_record_list;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::IndexMut;
 
 use absolute_index_set::AbsoluteIndexSet;
-// #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
-// use arbitrary::Arbitrary;
-// #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
-// use arbitrary::Result;
-// // #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
+#[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
+use arbitrary::Arbitrary;
 use get_size2::GetSize;
 use serde::Deserialize;
 use serde::Serialize;
@@ -17,18 +16,22 @@ use serde::Serialize;
 #[cfg(test)]
 pub use tests::propcompose_absindset;
 */
+use itertools::Itertools;
+use thiserror::Error;
 use twenty_first::math::bfield_codec::BFieldCodec;
-//use twenty_first::util_types::mmr::mmr_trait::Mmr;
+use twenty_first::prelude::Digest;
+use twenty_first::prelude::MmrMembershipProof;
+use twenty_first::prelude::Tip5 as Hash;
+use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+use twenty_first::util_types::mmr::mmr_trait::LeafMutation;
+use twenty_first::util_types::mmr::mmr_trait::Mmr;
 
-// use super::mutator_set_accumulator::MutatorSetAccumulator;
 use super::chunk_dictionary::ChunkDictionary;
-// use super::shared::get_batch_mutation_argument_for_removal_record;
-// use super::shared::indices_to_hash_map;
-// use super::shared::BATCH_SIZE;
-// use super::shared::CHUNK_SIZE;
-// use super::MutatorSetError;
-// use crate::models::blockchain::shared::Hash;
-// use crate::prelude::twenty_first;
+use super::mutator_set_accumulator::MutatorSetAccumulator;
+use super::shared::BATCH_SIZE;
+use super::shared::CHUNK_SIZE;
+use super::shared::get_batch_mutation_argument_for_removal_record;
+use super::shared::indices_to_hash_map;
 
 //#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, GetSize, BFieldCodec, TasmObject)]
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, GetSize, BFieldCodec)]
@@ -37,7 +40,7 @@ pub struct RemovalRecord {
     pub absolute_indices: AbsoluteIndexSet,
     pub target_chunks: ChunkDictionary,
 }
-/*
+
 impl RemovalRecord {
     /// Update a batch of removal records that are synced to a given mutator set, in anticipation
     /// of one addition to that mutator set. (The addition record
@@ -72,8 +75,7 @@ impl RemovalRecord {
         // size of gigabytes, whereas the MMR accumulator should be in the size of
         // kilobytes.
         let mut mmra: MmrAccumulator = mutator_set.swbf_inactive.to_accumulator();
-        let new_swbf_auth_path: mmr::mmr_membership_proof::MmrMembershipProof =
-            mmra.append(new_chunk_digest);
+        let new_swbf_auth_path: MmrMembershipProof = mmra.append(new_chunk_digest);
 
         // Collect all indices for all removal records that are being updated
         let mut chunk_index_to_rr_index: HashMap<u64, Vec<usize>> = HashMap::new();
@@ -133,9 +135,7 @@ impl RemovalRecord {
         // repeated since the MMR `batch_update_from_append` handles this optimally.
         // So relegating that bookkeeping to this function instead would not be more
         // efficient.
-        let mut mmr_membership_proofs_for_append: Vec<
-            &mut mmr::mmr_membership_proof::MmrMembershipProof,
-        > = vec![];
+        let mut mmr_membership_proofs_for_append: Vec<&mut MmrMembershipProof> = vec![];
         let mut leaf_indices = vec![];
         for (i, rr) in removal_records.iter_mut().enumerate() {
             if rrs_for_batch_append.contains(&i) {
@@ -149,7 +149,7 @@ impl RemovalRecord {
         }
 
         // Perform the update of all the MMR membership proofs contained in the removal records
-        mmr::mmr_membership_proof::MmrMembershipProof::batch_update_from_append(
+        MmrMembershipProof::batch_update_from_append(
             &mut mmr_membership_proofs_for_append,
             &leaf_indices,
             mutator_set.swbf_inactive.num_leafs(),
@@ -175,7 +175,7 @@ impl RemovalRecord {
             );
 
         // Collect all the MMR membership proofs from the chunk dictionaries.
-        let mut own_mmr_mps: Vec<&mut mmr::mmr_membership_proof::MmrMembershipProof> = vec![];
+        let mut own_mmr_mps: Vec<&mut MmrMembershipProof> = vec![];
         let mut leaf_indices = vec![];
         for chunk_dict in &mut chunk_dictionaries {
             for (chunk_index, (mp, _)) in chunk_dict.iter_mut() {
@@ -185,7 +185,7 @@ impl RemovalRecord {
         }
 
         // Perform the batch mutation of the MMR membership proofs
-        mmr::mmr_membership_proof::MmrMembershipProof::batch_update_from_batch_leaf_mutation(
+        MmrMembershipProof::batch_update_from_batch_leaf_mutation(
             &mut own_mmr_mps,
             &leaf_indices,
             mutation_argument
@@ -216,11 +216,11 @@ impl RemovalRecord {
     /// part of the SWBF, and that all required chunk/MMR membership proofs are
     /// present.
     pub fn validate(&self, mutator_set: &MutatorSetAccumulator) -> bool {
-        self.validate_inner(mutator_set).is_ok()
+        self.validate_detailed(mutator_set).is_ok()
     }
 
     /// Same as [`Self::validate`] but with informative error code.
-    pub(crate) fn validate_inner(
+    pub fn validate_detailed(
         &self,
         mutator_set: &MutatorSetAccumulator,
     ) -> Result<(), RemovalRecordValidityError> {
@@ -252,14 +252,15 @@ impl RemovalRecord {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum RemovalRecordValidityError {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum RemovalRecordValidityError {
+    #[error("removal record lacks an authenticated chunk required by its indices")]
     AbsentAuthenticatedChunk,
+
+    #[error("invalid MMR membership proof into the inactive SWBF for chunk {chunk_index}")]
     InvalidSwbfiMmrMp { chunk_index: u64 },
 }
 
-*/
-
 /*
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
@@ -767,6 +768,97 @@ mod tests {
 }
 */
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutator_set::commit;
+    use crate::mutator_set::ms_membership_proof::MsMembershipProof;
+
+    mod nc {
+        pub use neptune_cash::util_types::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+        pub use neptune_cash::util_types::mutator_set::removal_record::RemovalRecord;
+    }
+
+    fn convert<T: Serialize, U: serde::de::DeserializeOwned>(value: &T) -> U {
+        bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap()
+    }
+
+    fn add_random_item(msa: &mut MutatorSetAccumulator) -> (Digest, MsMembershipProof) {
+        let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+        let membership_proof = msa.prove(item, sender_randomness, receiver_preimage);
+        msa.add(&commit(item, sender_randomness, receiver_preimage.hash()));
+        (item, membership_proof)
+    }
+
+    #[test]
+    fn batch_updates_agree_with_neptune_cash() {
+        let mut msa = MutatorSetAccumulator::default();
+        let (item, mp) = add_random_item(&mut msa);
+        let mut removal_record = msa.drop(item, &mp);
+        let mut nc_removal_record: nc::RemovalRecord = convert(&removal_record);
+
+        // slide the window often enough for the removal record to pick up
+        // inactive chunks
+        for _ in 0..20 * BATCH_SIZE {
+            let nc_msa: nc::MutatorSetAccumulator = convert(&msa);
+            RemovalRecord::batch_update_from_addition(&mut [&mut removal_record], &msa);
+            nc::RemovalRecord::batch_update_from_addition(&mut [&mut nc_removal_record], &nc_msa);
+            add_random_item(&mut msa);
+
+            assert_eq!(
+                bincode::serialize(&nc_removal_record).unwrap(),
+                bincode::serialize(&removal_record).unwrap()
+            );
+            assert!(removal_record.validate(&msa));
+        }
+
+        // apply another item's removal and update ours accordingly
+        let (other_item, other_mp) = add_random_item(&mut msa);
+        RemovalRecord::batch_update_from_addition(&mut [&mut removal_record], &msa);
+        let applied_removal_record = msa.drop(other_item, &other_mp);
+        let nc_applied_removal_record: nc::RemovalRecord = convert(&applied_removal_record);
+        let mut nc_removal_record: nc::RemovalRecord = convert(&removal_record);
+
+        RemovalRecord::batch_update_from_remove(
+            &mut [&mut removal_record],
+            &applied_removal_record,
+        );
+        nc::RemovalRecord::batch_update_from_remove(
+            &mut [&mut nc_removal_record],
+            &nc_applied_removal_record,
+        );
+        msa.remove(&applied_removal_record);
+
+        assert_eq!(
+            bincode::serialize(&nc_removal_record).unwrap(),
+            bincode::serialize(&removal_record).unwrap()
+        );
+        assert_eq!(Ok(()), removal_record.validate_detailed(&msa));
+        assert!(msa.can_remove(&removal_record));
+    }
+
+    #[test]
+    fn validate_detailed_reports_absent_chunk() {
+        let mut msa = MutatorSetAccumulator::default();
+        let (item, mp) = add_random_item(&mut msa);
+        let mut removal_record = msa.drop(item, &mp);
+
+        // slide the window until at least one of the item's indices lives in
+        // an inactive chunk
+        while removal_record.target_chunks.is_empty() {
+            RemovalRecord::batch_update_from_addition(&mut [&mut removal_record], &msa);
+            add_random_item(&mut msa);
+        }
+        let chunk_index = removal_record.target_chunks.all_chunk_indices()[0];
+
+        removal_record.target_chunks.remove(&chunk_index);
+        assert_eq!(
+            Err(RemovalRecordValidityError::AbsentAuthenticatedChunk),
+            removal_record.validate_detailed(&msa)
+        );
+        assert!(!removal_record.validate(&msa));
+    }
+}
 
 #[cfg(test)]
 #[allow(unused_imports)]
@@ -1,22 +1,26 @@
-// #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
-// use arbitrary::Arbitrary;
-// #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
-// use arbitrary::Result;
-// #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
-// use arbitrary::Unstructured;
+#[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
+use arbitrary::Arbitrary;
+#[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
+use arbitrary::Unstructured;
+use std::collections::HashMap;
+
 use get_size2::GetSize;
+use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
 // use tasm_lib::structure::tasm_object::TasmObject;
 use twenty_first::math::bfield_codec::BFieldCodec;
-
-// use super::super::mutator_set_accumulator::MutatorSetAccumulator;
+use twenty_first::prelude::Digest;
+use twenty_first::prelude::Sponge;
+use twenty_first::prelude::Tip5;
+
+use super::super::MutatorSetError;
+use super::super::mutator_set_accumulator::MutatorSetAccumulator;
+use super::super::shared::BATCH_SIZE;
+use super::super::shared::CHUNK_SIZE;
 use super::super::shared::NUM_TRIALS;
-// use super::MutatorSetError;
-// use crate::util_types::mutator_set::shared::indices_to_hash_map;
-// use crate::util_types::mutator_set::shared::BATCH_SIZE;
-// use crate::util_types::mutator_set::shared::CHUNK_SIZE;
-// use crate::util_types::mutator_set::shared::WINDOW_SIZE;
+use super::super::shared::WINDOW_SIZE;
+use super::super::shared::indices_to_hash_map;
 
 /// A set of 45 (=[`NUM_TRIALS`]) sliding window Bloom filter bit indices.
 /// The indices live in a window that is at most 2^20 (=[`WINDOW_SIZE`]) wide.
@@ -45,22 +49,6 @@ impl GetSize for AbsoluteIndexSet {
     }
 }
 
-
-impl AbsoluteIndexSet {
-    pub fn to_vec(self) -> Vec<u128> {
-        self.to_array().to_vec()
-    }
-
-    pub fn to_array(self) -> [u128; NUM_TRIALS as usize] {
-        // Saturating add to guard overflow caused by malicious absolute index
-        // sets. Malicious absolute index sets will not have a valid proof, so
-        // there is no risk of applying such objects to the mutator set.
-        self.distances
-            .map(|x| u128::from(x).saturating_add(self.minimum))
-    }
-}
-
-/*
 impl AbsoluteIndexSet {
     /// Construct a new [`AbsoluteIndexSet`] from an array of [`NUM_TRIALS`]-
     /// many `u128`s.
@@ -92,7 +80,23 @@ impl AbsoluteIndexSet {
         Self { minimum, distances }
     }
 
+    pub fn to_vec(self) -> Vec<u128> {
+        self.to_array().to_vec()
+    }
+
+    pub fn to_array(self) -> [u128; NUM_TRIALS as usize] {
+        // Saturating add to guard overflow caused by malicious absolute index
+        // sets. Malicious absolute index sets will not have a valid proof, so
+        // there is no risk of applying such objects to the mutator set.
+        self.distances
+            .map(|x| u128::from(x).saturating_add(self.minimum))
+    }
+
     /// Get the (absolute) indices for removing this item from the mutator set.
+    ///
+    /// The indices are sampled from a sponge that absorbed the item, the
+    /// sender randomness, the receiver preimage, and the item's index in the
+    /// AOCL. They live in the window that was active when the item was added.
     pub fn compute(
         item: Digest,
         sender_randomness: Digest,
@@ -134,7 +138,7 @@ impl AbsoluteIndexSet {
     /// Returns an error if a removal index is a future value, i.e. one that's
     /// not yet covered by the active window.
     #[expect(clippy::type_complexity)]
-    pub(crate) fn split_by_activity(
+    pub fn split_by_activity(
         &self,
         mutator_set: &MutatorSetAccumulator,
     ) -> Result<(HashMap<u64, Vec<u128>>, Vec<u128>), MutatorSetError> {
@@ -159,12 +163,12 @@ impl AbsoluteIndexSet {
 
 #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
 impl<'a> Arbitrary<'a> for AbsoluteIndexSet {
-    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
         let aocl_index = u64::arbitrary(u)? >> 1;
         let window_start = u128::from(aocl_index) / u128::from(BATCH_SIZE) * u128::from(CHUNK_SIZE);
         let mut relative_indices = vec![];
         for _ in 0..NUM_TRIALS {
-            let index = u32::arbitrary(u)? & (super::super::shared::WINDOW_SIZE - 1);
+            let index = u32::arbitrary(u)? & (WINDOW_SIZE - 1);
             relative_indices.push(index);
         }
         let absolute_indices = relative_indices
@@ -176,7 +180,73 @@ impl<'a> Arbitrary<'a> for AbsoluteIndexSet {
         Ok(Self::new(absolute_indices))
     }
 }
-*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutator_set::commit;
+    use crate::mutator_set::ms_membership_proof::MsMembershipProof;
+
+    mod nc {
+        pub use neptune_cash::util_types::mutator_set::ms_membership_proof::MsMembershipProof;
+    }
+
+    #[test]
+    fn compute_agrees_with_neptune_cash() {
+        let mut msa = MutatorSetAccumulator::default();
+        for _ in 0..3 * BATCH_SIZE + 5 {
+            let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+            let mp = msa.prove(item, sender_randomness, receiver_preimage);
+            msa.add(&commit(item, sender_randomness, receiver_preimage.hash()));
+
+            let ours = AbsoluteIndexSet::compute(
+                item,
+                sender_randomness,
+                receiver_preimage,
+                mp.aocl_leaf_index,
+            );
+            assert_eq!(ours, mp.compute_indices(item));
+
+            let nc_mp: nc::MsMembershipProof =
+                bincode::deserialize(&bincode::serialize(&mp).unwrap()).unwrap();
+            let theirs = nc_mp.compute_indices(crate::test_shared::dg(item));
+            assert_eq!(
+                bincode::serialize(&theirs).unwrap(),
+                bincode::serialize(&ours).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn to_array_followed_by_new_is_identity() {
+        let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+        let ais = AbsoluteIndexSet::compute(item, sender_randomness, receiver_preimage, 1 << 40);
+        assert_eq!(ais, AbsoluteIndexSet::new(ais.to_array()));
+    }
+
+    #[test]
+    fn fresh_index_set_lives_in_active_window() {
+        let mut msa = MutatorSetAccumulator::default();
+        let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+        let mp: MsMembershipProof = msa.prove(item, sender_randomness, receiver_preimage);
+        msa.add(&commit(item, sender_randomness, receiver_preimage.hash()));
+
+        let (inactive, active) = mp.compute_indices(item).split_by_activity(&msa).unwrap();
+        assert!(inactive.is_empty());
+        assert_eq!(NUM_TRIALS as usize, active.len());
+    }
+
+    #[test]
+    fn future_index_set_is_rejected() {
+        let msa = MutatorSetAccumulator::default();
+        let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+        let ais = AbsoluteIndexSet::compute(item, sender_randomness, receiver_preimage, 1 << 20);
+        assert!(matches!(
+            ais.split_by_activity(&msa),
+            Err(MutatorSetError::AbsoluteRemovalIndexIsFutureIndex { .. })
+        ));
+    }
+}
+
 /*
 
 #[cfg(test)]
@@ -1,3 +1,77 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use twenty_first::prelude::*;
+
+use super::chunk::Chunk;
+use super::chunk_dictionary::ChunkDictionary;
+use super::removal_record::RemovalRecord;
+
 pub const WINDOW_SIZE: u32 = 1 << 20;
 pub const CHUNK_SIZE: u32 = 1 << 12;
+pub const BATCH_SIZE: u32 = 1 << 3;
 pub const NUM_TRIALS: u32 = 45;
+
+/// Group absolute Bloom filter indices by the chunk they belong to.
+pub fn indices_to_hash_map(all_indices: &[u128; NUM_TRIALS as usize]) -> HashMap<u64, Vec<u128>> {
+    let mut chunkidx_to_indices_dict: HashMap<u64, Vec<u128>> = HashMap::new();
+    for index in all_indices {
+        let chunk_index =
+            u64::try_from(index / u128::from(CHUNK_SIZE)).expect("Chunk index must fit in u64");
+        chunkidx_to_indices_dict
+            .entry(chunk_index)
+            .or_default()
+            .push(*index);
+    }
+
+    chunkidx_to_indices_dict
+}
+
+/// Apply the removal record's indices to the chunks of the inactive part of
+/// the SWBF, both the ones carried by the removal record and the ones found in
+/// the given chunk dictionaries.
+///
+/// Returns the indices of the chunk dictionaries in which some chunk changed,
+/// along with the (chunk index, MMR membership proof, new leaf) triples that
+/// must be applied to the SWBF MMR and to all MMR membership proofs into it.
+pub(crate) fn get_batch_mutation_argument_for_removal_record(
+    removal_record: &RemovalRecord,
+    chunk_dictionaries: &mut [&mut ChunkDictionary],
+) -> (HashSet<usize>, Vec<(u64, MmrMembershipProof, Digest)>) {
+    let rr_chunkidx_to_indices = removal_record.get_chunkidx_to_indices_dict();
+
+    // chunk index -> (MMR membership proof, chunk after removal)
+    let mut new_chunks: HashMap<u64, (MmrMembershipProof, Chunk)> = HashMap::new();
+    for (chunk_index, (mmr_mp, chunk)) in removal_record.target_chunks.iter() {
+        let mut new_chunk = chunk.clone();
+        for index in rr_chunkidx_to_indices
+            .get(chunk_index)
+            .into_iter()
+            .flatten()
+        {
+            new_chunk.insert((index % u128::from(CHUNK_SIZE)) as u32);
+        }
+        new_chunks.insert(*chunk_index, (mmr_mp.clone(), new_chunk));
+    }
+
+    let mut mutated_chunk_dictionaries = HashSet::new();
+    for (i, chunk_dictionary) in chunk_dictionaries.iter_mut().enumerate() {
+        for (chunk_index, (_mmr_mp, chunk)) in chunk_dictionary.iter_mut() {
+            if let Some((_, new_chunk)) = new_chunks.get(chunk_index)
+                && chunk != new_chunk
+            {
+                *chunk = new_chunk.clone();
+                mutated_chunk_dictionaries.insert(i);
+            }
+        }
+    }
+
+    let mutation_argument = new_chunks
+        .into_iter()
+        .sorted_by_key(|(chunk_index, _)| *chunk_index)
+        .map(|(chunk_index, (mmr_mp, chunk))| (chunk_index, mmr_mp, Tip5::hash(&chunk)))
+        .collect_vec();
+
+    (mutated_chunk_dictionaries, mutation_argument)
+}
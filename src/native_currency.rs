@@ -1,6 +1,15 @@
+use crate::block::MINING_REWARD_TIME_LOCK_PERIOD;
+use crate::native_currency_amount::NativeCurrencyAmount;
+use crate::primitive_witness::PrimitiveWitness;
+use crate::primitive_witness::SaltedUtxos;
+use crate::transaction_kernel::TransactionKernel;
+use crate::utxo::Utxo;
 use get_size2::GetSize;
+use num_traits::CheckedAdd;
+use num_traits::Zero;
 use serde::Deserialize;
 use serde::Serialize;
+use thiserror::Error;
 use twenty_first::prelude::*;
 /// `NativeCurrency` is the type script that governs Neptune's native currency,
 /// Neptune coins.
@@ -26,6 +35,169 @@ impl NativeCurrency {
         .unwrap()
     }
 }
+
+/// The rule of the [NativeCurrency] type script that a transaction violates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum NativeCurrencyError {
+    #[error("coinbase must not be negative, got {0}")]
+    NegativeCoinbase(NativeCurrencyAmount),
+
+    #[error("fee must not be negative if the transaction has a coinbase, got {0}")]
+    NegativeFeeWithCoinbase(NativeCurrencyAmount),
+
+    #[error("fee {0} is out of range")]
+    FeeOutOfRange(NativeCurrencyAmount),
+
+    #[error("input {0} holds a negative or malformed amount")]
+    InvalidInputAmount(usize),
+
+    #[error("output {0} holds a negative or malformed amount")]
+    InvalidOutputAmount(usize),
+
+    #[error(
+        "sum of amounts exceeds the maximum of {} nau",
+        NativeCurrencyAmount::MAX_NAU
+    )]
+    Overflow,
+
+    #[error(
+        "not enough funds time-locked: half of coinbase is {half_of_coinbase}, \
+         but time-locked outputs plus half of fee is {time_locked}"
+    )]
+    InsufficientTimeLock {
+        half_of_coinbase: NativeCurrencyAmount,
+        time_locked: NativeCurrencyAmount,
+    },
+
+    #[error(
+        "transaction is unbalanced: inputs plus coinbase is {inputs_plus_coinbase}, \
+         outputs plus fee is {outputs_plus_fee}"
+    )]
+    Unbalanced {
+        inputs_plus_coinbase: NativeCurrencyAmount,
+        outputs_plus_fee: NativeCurrencyAmount,
+    },
+}
+
+/// The part of a [PrimitiveWitness] that the [NativeCurrency] type script
+/// looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeCurrencyWitness {
+    pub salted_input_utxos: SaltedUtxos,
+    pub salted_output_utxos: SaltedUtxos,
+    pub kernel: TransactionKernel,
+}
+
+impl From<&PrimitiveWitness> for NativeCurrencyWitness {
+    fn from(primitive_witness: &PrimitiveWitness) -> Self {
+        Self {
+            salted_input_utxos: primitive_witness.input_utxos.clone(),
+            salted_output_utxos: primitive_witness.output_utxos.clone(),
+            kernel: primitive_witness.kernel.clone(),
+        }
+    }
+}
+
+impl NativeCurrencyWitness {
+    /// Check the transaction against the rules of the [NativeCurrency] type
+    /// script, without running the consensus program.
+    ///
+    /// A transaction that passes this check is balanced, but it can still be
+    /// invalid for other reasons, such as unsatisfied lock scripts.
+    pub fn check(&self) -> Result<(), NativeCurrencyError> {
+        let coinbase = self
+            .kernel
+            .coinbase
+            .unwrap_or_else(NativeCurrencyAmount::zero);
+        let fee = self.kernel.fee;
+        if coinbase.is_negative() {
+            return Err(NativeCurrencyError::NegativeCoinbase(coinbase));
+        }
+        if self.kernel.coinbase.is_some() && fee.is_negative() {
+            return Err(NativeCurrencyError::NegativeFeeWithCoinbase(fee));
+        }
+
+        let mut total_input = NativeCurrencyAmount::zero();
+        for (i, utxo) in self.salted_input_utxos.utxos.iter().enumerate() {
+            let amount = native_currency_amount(utxo, NativeCurrencyError::InvalidInputAmount(i))?;
+            total_input = total_input
+                .checked_add(&amount)
+                .ok_or(NativeCurrencyError::Overflow)?;
+        }
+
+        let time_lock_threshold = self.kernel.timestamp + MINING_REWARD_TIME_LOCK_PERIOD;
+        let mut total_output = NativeCurrencyAmount::zero();
+        let mut total_time_locked_output = NativeCurrencyAmount::zero();
+        for (i, utxo) in self.salted_output_utxos.utxos.iter().enumerate() {
+            let amount = native_currency_amount(utxo, NativeCurrencyError::InvalidOutputAmount(i))?;
+            total_output = total_output
+                .checked_add(&amount)
+                .ok_or(NativeCurrencyError::Overflow)?;
+
+            let is_time_locked = utxo
+                .coins()
+                .iter()
+                .filter_map(|coin| coin.release_date())
+                .any(|release_date| release_date >= time_lock_threshold);
+            if is_time_locked {
+                total_time_locked_output = total_time_locked_output
+                    .checked_add(&amount)
+                    .ok_or(NativeCurrencyError::Overflow)?;
+            }
+        }
+
+        if fee < NativeCurrencyAmount::min() || fee > NativeCurrencyAmount::max() {
+            return Err(NativeCurrencyError::FeeOutOfRange(fee));
+        }
+
+        let mut half_of_coinbase = coinbase;
+        half_of_coinbase.div_two();
+        let mut half_of_fee = fee;
+        half_of_fee.div_two();
+        let time_locked = total_time_locked_output + half_of_fee;
+        if !coinbase.is_zero() && half_of_coinbase > time_locked {
+            return Err(NativeCurrencyError::InsufficientTimeLock {
+                half_of_coinbase,
+                time_locked,
+            });
+        }
+
+        let inputs_plus_coinbase = total_input
+            .checked_add(&coinbase)
+            .ok_or(NativeCurrencyError::Overflow)?;
+        let outputs_plus_fee = total_output
+            .checked_add(&fee)
+            .ok_or(NativeCurrencyError::Overflow)?;
+        if inputs_plus_coinbase != outputs_plus_fee {
+            return Err(NativeCurrencyError::Unbalanced {
+                inputs_plus_coinbase,
+                outputs_plus_fee,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The sum of the UTXO's native currency coins. Fails with `invalid` if any
+/// of them is negative or cannot be decoded.
+fn native_currency_amount(
+    utxo: &Utxo,
+    invalid: NativeCurrencyError,
+) -> Result<NativeCurrencyAmount, NativeCurrencyError> {
+    utxo.coins()
+        .iter()
+        .filter(|coin| coin.type_script_hash == NativeCurrency.hash())
+        .try_fold(NativeCurrencyAmount::zero(), |total, coin| {
+            let amount = *NativeCurrencyAmount::decode(&coin.state).map_err(|_| invalid)?;
+            if amount.is_negative() {
+                return Err(invalid);
+            }
+            total
+                .checked_add(&amount)
+                .ok_or(NativeCurrencyError::Overflow)
+        })
+}
 ///# [cfg (test)]
 #[cfg(all(test, feature = "original-tests"))]
 #[cfg_attr(coverage_nightly, coverage(off))]
@@ -653,6 +825,134 @@ pub mod tests {
     );
 }
 #[cfg(test)]
+mod balance_tests {
+    use super::*;
+    use crate::lock_script::LockScript;
+    use crate::time_lock::TimeLock;
+    use crate::timestamp::Timestamp;
+    use crate::transaction_kernel::TransactionKernelProxy;
+    use crate::utxo::Coin;
+
+    fn utxo(amount: NativeCurrencyAmount, release_date: Option<Timestamp>) -> Utxo {
+        let mut coins = vec![Coin::new_native_currency(amount)];
+        coins.extend(release_date.map(TimeLock::until));
+        Utxo::new(LockScript::anyone_can_spend(), coins)
+    }
+
+    fn witness(
+        inputs: Vec<Utxo>,
+        outputs: Vec<Utxo>,
+        fee: NativeCurrencyAmount,
+        coinbase: Option<NativeCurrencyAmount>,
+        timestamp: Timestamp,
+    ) -> NativeCurrencyWitness {
+        let kernel = TransactionKernelProxy {
+            inputs: vec![],
+            outputs: vec![],
+            announcements: vec![],
+            fee,
+            coinbase,
+            timestamp,
+            mutator_set_hash: Digest::default(),
+            merge_bit: false,
+        }
+        .into_kernel();
+        NativeCurrencyWitness {
+            salted_input_utxos: SaltedUtxos::new(inputs),
+            salted_output_utxos: SaltedUtxos::new(outputs),
+            kernel,
+        }
+    }
+
+    #[test]
+    fn inputs_must_balance_outputs_and_fee() {
+        let now = Timestamp::now();
+        let inputs = vec![utxo(NativeCurrencyAmount::coins(10), None)];
+        let outputs = vec![utxo(NativeCurrencyAmount::coins(9), None)];
+
+        let balanced = witness(
+            inputs.clone(),
+            outputs.clone(),
+            NativeCurrencyAmount::coins(1),
+            None,
+            now,
+        );
+        assert_eq!(Ok(()), balanced.check());
+
+        let unbalanced = witness(inputs, outputs, NativeCurrencyAmount::coins(2), None, now);
+        assert_eq!(
+            Err(NativeCurrencyError::Unbalanced {
+                inputs_plus_coinbase: NativeCurrencyAmount::coins(10),
+                outputs_plus_fee: NativeCurrencyAmount::coins(11),
+            }),
+            unbalanced.check()
+        );
+    }
+
+    #[test]
+    fn half_of_coinbase_must_be_time_locked() {
+        let now = Timestamp::now();
+        let coinbase = Some(NativeCurrencyAmount::coins(4));
+        let locked_until = |release_date| {
+            vec![
+                utxo(NativeCurrencyAmount::coins(2), Some(release_date)),
+                utxo(NativeCurrencyAmount::coins(2), None),
+            ]
+        };
+
+        let three_years = locked_until(now + MINING_REWARD_TIME_LOCK_PERIOD);
+        let valid = witness(
+            vec![],
+            three_years,
+            NativeCurrencyAmount::zero(),
+            coinbase,
+            now,
+        );
+        assert_eq!(Ok(()), valid.check());
+
+        let one_day = locked_until(now + Timestamp::days(1));
+        let invalid = witness(vec![], one_day, NativeCurrencyAmount::zero(), coinbase, now);
+        assert_eq!(
+            Err(NativeCurrencyError::InsufficientTimeLock {
+                half_of_coinbase: NativeCurrencyAmount::coins(2),
+                time_locked: NativeCurrencyAmount::zero(),
+            }),
+            invalid.check()
+        );
+
+        let negative_fee = witness(
+            vec![],
+            vec![],
+            -NativeCurrencyAmount::coins(1),
+            coinbase,
+            now,
+        );
+        assert_eq!(
+            Err(NativeCurrencyError::NegativeFeeWithCoinbase(
+                -NativeCurrencyAmount::coins(1)
+            )),
+            negative_fee.check()
+        );
+    }
+
+    #[test]
+    fn sums_must_not_overflow() {
+        let max = NativeCurrencyAmount::from_nau(NativeCurrencyAmount::MAX_NAU);
+        let outputs = vec![
+            utxo(max, None),
+            utxo(NativeCurrencyAmount::from_nau(1), None),
+        ];
+        let overflowing = witness(
+            vec![utxo(max, None)],
+            outputs,
+            NativeCurrencyAmount::zero(),
+            None,
+            Timestamp::now(),
+        );
+        assert_eq!(Err(NativeCurrencyError::Overflow), overflowing.check());
+    }
+}
+#[cfg(test)]
 #[allow(unused_imports)]
 #[allow(unused_variables)]
 #[allow(unreachable_code)]
@@ -7,10 +7,13 @@
 use crate::address::ReceivingAddress;
 use crate::native_currency_amount::NativeCurrencyAmount;
 use crate::tx_output::TxOutput;
+use crate::tx_output::TxOutputList;
+use crate::tx_output::UtxoUnlockable;
 use crate::utxo::Utxo;
 use crate::utxo_notification::UtxoNotificationMedium;
 use serde::Deserialize;
 use serde::Serialize;
+use twenty_first::prelude::Digest;
 /// enumerates various ways to specify a transaction output as a simple tuple.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum OutputFormat {
@@ -84,6 +87,181 @@ impl From<TxOutput> for OutputFormat {
         Self::TxOutput(v)
     }
 }
+
+/// builds a [TxOutputList] from a list of [OutputFormat]s.
+///
+/// outputs that do not specify a notification medium use the default medium
+/// for owned or unowned UTXOs, depending on whether the wallet can unlock the
+/// UTXO.
+#[derive(Debug, Clone, Default)]
+pub struct TxOutputListBuilder {
+    outputs: Vec<OutputFormat>,
+    owned_utxo_notification_medium: UtxoNotificationMedium,
+    unowned_utxo_notification_medium: UtxoNotificationMedium,
+}
+
+impl TxOutputListBuilder {
+    /// instantiate with on-chain notification for owned and unowned UTXOs
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// set the notification medium for outputs the wallet can unlock
+    pub fn owned_utxo_notification_medium(mut self, medium: UtxoNotificationMedium) -> Self {
+        self.owned_utxo_notification_medium = medium;
+        self
+    }
+
+    /// set the notification medium for outputs the wallet cannot unlock
+    pub fn unowned_utxo_notification_medium(mut self, medium: UtxoNotificationMedium) -> Self {
+        self.unowned_utxo_notification_medium = medium;
+        self
+    }
+
+    /// add an output
+    pub fn output(mut self, output: impl Into<OutputFormat>) -> Self {
+        self.outputs.push(output.into());
+        self
+    }
+
+    /// add a list of outputs
+    pub fn outputs(mut self, outputs: impl IntoIterator<Item = impl Into<OutputFormat>>) -> Self {
+        self.outputs.extend(outputs.into_iter().map(|o| o.into()));
+        self
+    }
+
+    /// build the [TxOutputList]
+    ///
+    /// `sender_randomness` maps the privacy digest of a receiving address to
+    /// the sender randomness of the output, for example:
+    ///
+    /// ```ignore
+    /// builder.build(&wallet, |receiver_digest| {
+    ///     wallet_entropy.generate_sender_randomness(block_height, receiver_digest)
+    /// })
+    /// ```
+    ///
+    /// outputs given as [OutputFormat::TxOutput] are used as-is.
+    pub fn build(
+        self,
+        utxo_unlockable: &impl UtxoUnlockable,
+        sender_randomness: impl Fn(Digest) -> Digest,
+    ) -> TxOutputList {
+        let owned_medium = self.owned_utxo_notification_medium;
+        let unowned_medium = self.unowned_utxo_notification_medium;
+        self.outputs
+            .into_iter()
+            .map(|output| match output {
+                OutputFormat::AddressAndAmount(address, amount) => TxOutput::auto(
+                    utxo_unlockable,
+                    address.clone(),
+                    amount,
+                    sender_randomness(address.privacy_digest()),
+                    owned_medium,
+                    unowned_medium,
+                ),
+                OutputFormat::AddressAndAmountAndMedium(address, amount, medium) => {
+                    let utxo = Utxo::new_native_currency(address.lock_script(), amount);
+                    let owned = utxo_unlockable.can_unlock(&utxo);
+                    TxOutput::native_currency(
+                        amount,
+                        sender_randomness(address.privacy_digest()),
+                        address,
+                        medium,
+                        owned,
+                    )
+                }
+                OutputFormat::AddressAndUtxo(address, utxo) => TxOutput::auto_utxo(
+                    utxo_unlockable,
+                    utxo,
+                    address.clone(),
+                    sender_randomness(address.privacy_digest()),
+                    owned_medium,
+                    unowned_medium,
+                ),
+                OutputFormat::AddressAndUtxoAndMedium(address, utxo, medium) => {
+                    let owned = utxo_unlockable.can_unlock(&utxo);
+                    let sender_randomness = sender_randomness(address.privacy_digest());
+                    match medium {
+                        UtxoNotificationMedium::OnChain => {
+                            TxOutput::onchain_utxo(utxo, sender_randomness, address, owned)
+                        }
+                        UtxoNotificationMedium::OffChain => {
+                            TxOutput::offchain_utxo(utxo, sender_randomness, address, owned)
+                        }
+                    }
+                }
+                OutputFormat::TxOutput(tx_output) => tx_output,
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::SpendingKey;
+    use crate::block_height::BlockHeight;
+    use crate::wallet_entropy::WalletEntropy;
+
+    struct Wallet(Vec<SpendingKey>);
+
+    impl UtxoUnlockable for Wallet {
+        fn can_unlock(&self, utxo: &Utxo) -> bool {
+            self.0
+                .iter()
+                .any(|key| key.lock_script_hash() == utxo.lock_script_hash())
+        }
+    }
+
+    #[test]
+    fn outputs_respect_ownership_and_medium() {
+        let wallet_entropy = WalletEntropy::devnet_wallet();
+        let own_key = SpendingKey::from(wallet_entropy.nth_symmetric_key(0));
+        let other_key = SpendingKey::from(wallet_entropy.nth_generation_spending_key(1));
+        let wallet = Wallet(vec![own_key]);
+        let own_address = own_key.to_address();
+        let other_address = other_key.to_address();
+        let amount = NativeCurrencyAmount::coins(3);
+        let utxo = Utxo::new_native_currency(other_address.lock_script(), amount);
+        let sender_randomness = |receiver_digest| {
+            wallet_entropy.generate_sender_randomness(BlockHeight::genesis(), receiver_digest)
+        };
+
+        let tx_outputs = TxOutputListBuilder::new()
+            .owned_utxo_notification_medium(UtxoNotificationMedium::OffChain)
+            .output((own_address.clone(), amount))
+            .output((other_address.clone(), amount))
+            .output((own_address.clone(), amount, UtxoNotificationMedium::OnChain))
+            .outputs([(
+                other_address.clone(),
+                utxo.clone(),
+                UtxoNotificationMedium::OffChain,
+            )])
+            .build(&wallet, sender_randomness);
+
+        let owned = tx_outputs.iter().map(|o| o.is_owned()).collect::<Vec<_>>();
+        let offchain = tx_outputs
+            .iter()
+            .map(|o| o.is_offchain())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![true, false, true, false], owned);
+        assert_eq!(vec![true, false, false, true], offchain);
+        assert!(tx_outputs.iter().all(|o| !o.is_change()));
+        assert_eq!(amount.scalar_mul(4), tx_outputs.total_native_coins());
+
+        assert_eq!(utxo, tx_outputs[3].utxo());
+        assert_eq!(
+            sender_randomness(other_address.privacy_digest()),
+            tx_outputs[3].sender_randomness()
+        );
+        assert_eq!(
+            own_address.privacy_digest(),
+            tx_outputs[0].receiver_digest()
+        );
+    }
+}
 #[cfg(test)]
 #[allow(unused_imports)]
 #[allow(unused_variables)]
@@ -0,0 +1,187 @@
+use crate::lock_script::LockScriptAndWitness;
+use crate::mutator_set::ms_membership_proof::MsMembershipProof;
+use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+use crate::transaction_kernel::TransactionKernel;
+use crate::type_script::TypeScriptAndWitness;
+use crate::utxo::Utxo;
+use get_size2::GetSize;
+use num_traits::ConstZero;
+use serde::Deserialize;
+use serde::Serialize;
+use twenty_first::prelude::*;
+/// A list of UTXOs with an associated salt.
+///
+/// The salt hides the UTXOs when only their hash is public, as is the case in
+/// the claims that the transaction's type scripts prove.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub struct SaltedUtxos {
+    pub utxos: Vec<Utxo>,
+    pub salt: [BFieldElement; 3],
+}
+
+impl SaltedUtxos {
+    /// Salt the UTXOs with fresh randomness.
+    pub fn new(utxos: Vec<Utxo>) -> Self {
+        Self {
+            utxos,
+            salt: rand::random(),
+        }
+    }
+    pub fn empty() -> Self {
+        Self {
+            utxos: vec![],
+            salt: [BFieldElement::ZERO; 3],
+        }
+    }
+}
+
+/// All the data needed to prove that a transaction is valid, including
+/// secrets such as the witnesses of the input lock scripts.
+///
+/// A prover turns a `PrimitiveWitness` into a
+/// [ProofCollection](crate::transaction_proof_type::TransactionProofType::ProofCollection)
+/// and eventually a
+/// [SingleProof](crate::transaction_proof_type::TransactionProofType::SingleProof),
+/// after which it can be discarded.
+///
+/// security: This type contains secrets and should only be shared with a
+/// trusted prover.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub struct PrimitiveWitness {
+    pub input_utxos: SaltedUtxos,
+    pub input_membership_proofs: Vec<MsMembershipProof>,
+    pub lock_scripts_and_witnesses: Vec<LockScriptAndWitness>,
+    pub type_scripts_and_witnesses: Vec<TypeScriptAndWitness>,
+    pub output_utxos: SaltedUtxos,
+    pub output_sender_randomnesses: Vec<Digest>,
+    pub output_receiver_digests: Vec<Digest>,
+    pub mutator_set_accumulator: MutatorSetAccumulator,
+    pub kernel: TransactionKernel,
+}
+
+#[cfg(test)]
+#[allow(unused_imports)]
+#[allow(unused_variables)]
+#[allow(unreachable_code)]
+#[allow(non_snake_case)]
+mod generated_tests {
+    use super::*;
+    use crate::announcement::Announcement;
+    use crate::lock_script::LockScript;
+    use crate::mutator_set::addition_record::AdditionRecord;
+    use crate::native_currency_amount::NativeCurrencyAmount;
+    use crate::test_shared::*;
+    use crate::timestamp::Timestamp;
+    use crate::transaction_kernel::TransactionKernelProxy;
+    use triton_isa::program::Program;
+    pub mod nc {
+        pub use neptune_cash::api::export::AdditionRecord;
+        pub use neptune_cash::api::export::Announcement;
+        pub use neptune_cash::api::export::NativeCurrencyAmount;
+        pub use neptune_cash::api::export::Timestamp;
+        pub use neptune_cash::prelude::triton_vm::prelude::Program;
+        pub use neptune_cash::prelude::twenty_first::prelude::BFieldElement;
+        pub use neptune_cash::protocol::consensus::transaction::lock_script::LockScript;
+        pub use neptune_cash::protocol::consensus::transaction::primitive_witness::PrimitiveWitness;
+        pub use neptune_cash::protocol::consensus::transaction::primitive_witness::SaltedUtxos;
+        pub use neptune_cash::protocol::consensus::transaction::transaction_kernel::TransactionKernelProxy;
+        pub use neptune_cash::protocol::consensus::transaction::utxo::Coin;
+        pub use neptune_cash::protocol::consensus::transaction::utxo::Utxo;
+        pub use neptune_cash::protocol::consensus::type_scripts::TypeScriptAndWitness;
+        pub use neptune_cash::util_types::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+    }
+
+    const TIMESTAMP_MILLIS: u64 = 1_751_000_000_000;
+    const TYPE_SCRIPT_CODE: &str = "push 1 assert halt";
+
+    fn salted_utxos() -> (SaltedUtxos, nc::SaltedUtxos) {
+        let ours = SaltedUtxos {
+            utxos: vec![Utxo::new_native_currency(
+                LockScript::anyone_can_spend(),
+                NativeCurrencyAmount::coins(3),
+            )],
+            salt: bfe_array![1, 2, 3],
+        };
+        let nc = nc::SaltedUtxos {
+            utxos: vec![nc::Utxo::new(
+                nc::LockScript::anyone_can_spend().hash(),
+                vec![nc::Coin::new_native_currency(
+                    nc::NativeCurrencyAmount::coins(3),
+                )],
+            )],
+            salt: [1, 2, 3].map(nc::BFieldElement::new),
+        };
+        (ours, nc)
+    }
+
+    fn primitive_witness() -> (PrimitiveWitness, nc::PrimitiveWitness) {
+        let (input_utxos, nc_input_utxos) = salted_utxos();
+        let addition_record = rand::random();
+        let sender_randomness = rand::random();
+
+        let kernel = TransactionKernelProxy {
+            inputs: vec![],
+            outputs: vec![AdditionRecord::new(addition_record)],
+            announcements: vec![Announcement::new(bfe_vec![5])],
+            fee: NativeCurrencyAmount::coins(3),
+            coinbase: None,
+            timestamp: Timestamp::millis(TIMESTAMP_MILLIS),
+            mutator_set_hash: MutatorSetAccumulator::default().hash(),
+            merge_bit: false,
+        }
+        .into_kernel();
+        let ours = PrimitiveWitness {
+            input_utxos,
+            input_membership_proofs: vec![],
+            lock_scripts_and_witnesses: vec![],
+            type_scripts_and_witnesses: vec![TypeScriptAndWitness::new_with_tokens(
+                Program::from_code(TYPE_SCRIPT_CODE).unwrap(),
+                bfe_vec![4, 5, 6],
+            )],
+            output_utxos: SaltedUtxos::empty(),
+            output_sender_randomnesses: vec![sender_randomness],
+            output_receiver_digests: vec![],
+            mutator_set_accumulator: MutatorSetAccumulator::default(),
+            kernel,
+        };
+
+        let nc_kernel = nc::TransactionKernelProxy {
+            inputs: vec![],
+            outputs: vec![nc::AdditionRecord::new(dg(addition_record))],
+            announcements: vec![nc::Announcement::new(vec![nc::BFieldElement::new(5)])],
+            fee: nc::NativeCurrencyAmount::coins(3),
+            coinbase: None,
+            timestamp: nc::Timestamp::millis(TIMESTAMP_MILLIS),
+            mutator_set_hash: nc::MutatorSetAccumulator::default().hash(),
+            merge_bit: false,
+        }
+        .into_kernel();
+        let nc = nc::PrimitiveWitness {
+            input_utxos: nc_input_utxos,
+            input_membership_proofs: vec![],
+            lock_scripts_and_witnesses: vec![],
+            type_scripts_and_witnesses: vec![nc::TypeScriptAndWitness::new_with_tokens(
+                nc::Program::from_code(TYPE_SCRIPT_CODE).unwrap(),
+                [4, 5, 6].map(nc::BFieldElement::new).to_vec(),
+            )],
+            output_utxos: nc::SaltedUtxos::empty(),
+            output_sender_randomnesses: vec![dg(sender_randomness)],
+            output_receiver_digests: vec![],
+            mutator_set_accumulator: nc::MutatorSetAccumulator::default(),
+            kernel: nc_kernel,
+        };
+
+        (ours, nc)
+    }
+
+    #[test]
+    fn test_bincode_serialization_for_salted_utxos() {
+        let (original_instance, nc_instance) = salted_utxos();
+        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+    }
+    #[test]
+    fn test_bincode_serialization_for_primitive_witness() {
+        let (original_instance, nc_instance) = primitive_witness();
+        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::triton_vm::proof::Proof;
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use twenty_first::prelude::*;
+/// A collection of proofs that, together, establish the validity of a
+/// transaction.
+///
+/// Unlike a [SingleProof](crate::transaction_proof::TransactionProof::SingleProof),
+/// a `ProofCollection` cannot be confirmed into a block, but it does not expose
+/// any secrets and can be shared with peers, who can raise it to a single
+/// proof.
+///
+/// The proofs themselves are kept opaque.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub struct ProofCollection {
+    pub removal_records_integrity: Proof,
+    pub collect_lock_scripts: Proof,
+    pub lock_scripts_halt: Vec<Proof>,
+    pub kernel_mast_hash: Digest,
+    pub collect_type_scripts: Proof,
+    pub type_scripts_halt: Vec<Proof>,
+    pub lock_script_hashes: Vec<Digest>,
+    pub type_script_hashes: Vec<Digest>,
+    pub salted_inputs_hash: Digest,
+    pub salted_outputs_hash: Digest,
+    pub merge_bit_mast_path: Vec<Digest>,
+}
+#[cfg(test)]
+#[allow(unused_imports)]
+#[allow(unused_variables)]
+#[allow(unreachable_code)]
+#[allow(non_snake_case)]
+pub(crate) mod generated_tests {
+    use super::*;
+    use crate::test_shared::*;
+    use itertools::Itertools;
+    pub mod nc {
+        pub use neptune_cash::prelude::triton_vm::prelude::Proof;
+        pub use neptune_cash::prelude::twenty_first::prelude::BFieldElement;
+        pub use neptune_cash::protocol::consensus::transaction::validity::proof_collection::ProofCollection;
+    }
+
+    /// A random proof, and the same proof as neptune-cash's type.
+    pub(crate) fn random_proof() -> (Proof, nc::Proof) {
+        let proof_size = rand::random_range(1..100);
+        let elements = (0..proof_size)
+            .map(|_| rand::random::<BFieldElement>())
+            .collect_vec();
+        let nc_elements = elements
+            .iter()
+            .map(|element| nc::BFieldElement::new(element.value()))
+            .collect();
+        (Proof(elements), nc::Proof(nc_elements))
+    }
+
+    /// A random proof collection, and the same proof collection as
+    /// neptune-cash's type.
+    pub(crate) fn random_proof_collection() -> (ProofCollection, nc::ProofCollection) {
+        let (removal_records_integrity, nc_removal_records_integrity) = random_proof();
+        let (collect_lock_scripts, nc_collect_lock_scripts) = random_proof();
+        let (lock_scripts_halt, nc_lock_scripts_halt) =
+            [random_proof(), random_proof()].into_iter().unzip();
+        let kernel_mast_hash = rand::random();
+        let (collect_type_scripts, nc_collect_type_scripts) = random_proof();
+        let (type_scripts_halt, nc_type_scripts_halt) = [random_proof()].into_iter().unzip();
+        let lock_script_hashes = rand::random::<[Digest; 2]>().to_vec();
+        let type_script_hashes = rand::random::<[Digest; 1]>().to_vec();
+        let salted_inputs_hash = rand::random();
+        let salted_outputs_hash = rand::random();
+        let merge_bit_mast_path = rand::random::<[Digest; 3]>().to_vec();
+
+        let nc_proof_collection = nc::ProofCollection {
+            removal_records_integrity: nc_removal_records_integrity,
+            collect_lock_scripts: nc_collect_lock_scripts,
+            lock_scripts_halt: nc_lock_scripts_halt,
+            kernel_mast_hash: dg(kernel_mast_hash),
+            collect_type_scripts: nc_collect_type_scripts,
+            type_scripts_halt: nc_type_scripts_halt,
+            lock_script_hashes: lock_script_hashes.iter().copied().map(dg).collect(),
+            type_script_hashes: type_script_hashes.iter().copied().map(dg).collect(),
+            salted_inputs_hash: dg(salted_inputs_hash),
+            salted_outputs_hash: dg(salted_outputs_hash),
+            merge_bit_mast_path: merge_bit_mast_path.iter().copied().map(dg).collect(),
+        };
+        let proof_collection = ProofCollection {
+            removal_records_integrity,
+            collect_lock_scripts,
+            lock_scripts_halt,
+            kernel_mast_hash,
+            collect_type_scripts,
+            type_scripts_halt,
+            lock_script_hashes,
+            type_script_hashes,
+            salted_inputs_hash,
+            salted_outputs_hash,
+            merge_bit_mast_path,
+        };
+
+        (proof_collection, nc_proof_collection)
+    }
+
+    #[test]
+    fn test_bincode_serialization_for_proof_collection() {
+        let (original_instance, nc_instance) = random_proof_collection();
+        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+    }
+}
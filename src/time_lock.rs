@@ -5,6 +5,7 @@ use get_size2::GetSize;
 use num_traits::Zero;
 use serde::Deserialize;
 use serde::Serialize;
+use thiserror::Error;
 use twenty_first::prelude::*;
 #[derive(Debug, Copy, Clone, Deserialize, Serialize, BFieldCodec, GetSize, PartialEq, Eq)]
 pub struct TimeLock;
@@ -32,6 +33,66 @@ impl TimeLock {
         )
         .unwrap()
     }
+
+    /// Find the inputs that the `TimeLock` type script forbids spending in a
+    /// transaction with the given timestamp.
+    ///
+    /// Like the consensus program, this requires every time-lock coin of an
+    /// input to hold exactly one element, and its release date to lie
+    /// strictly before `timestamp`. Outputs are not constrained.
+    pub fn violations(input_utxos: &[Utxo], timestamp: Timestamp) -> Vec<TimeLockViolation> {
+        input_utxos
+            .iter()
+            .enumerate()
+            .filter_map(|(index, utxo)| Self::violation(index, utxo, timestamp))
+            .collect()
+    }
+
+    fn violation(index: usize, utxo: &Utxo, timestamp: Timestamp) -> Option<TimeLockViolation> {
+        let mut latest_release_date = None;
+        for coin in utxo.coins() {
+            if coin.type_script_hash != TimeLock.hash() {
+                continue;
+            }
+            let [release_date] = coin.state[..] else {
+                return Some(TimeLockViolation::MalformedState { index });
+            };
+            let release_date = Timestamp(release_date);
+            if release_date >= timestamp {
+                latest_release_date = latest_release_date.max(Some(release_date));
+            }
+        }
+
+        latest_release_date.map(|release_date| TimeLockViolation::Locked {
+            index,
+            release_date,
+        })
+    }
+}
+
+/// An input that cannot be spent because of its time-lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum TimeLockViolation {
+    /// The input is spendable in transactions whose timestamp is later than
+    /// `release_date`.
+    #[error("input {index} is time-locked until {release_date}")]
+    Locked {
+        index: usize,
+        release_date: Timestamp,
+    },
+
+    /// The input's time-lock coin is malformed, so it can never be spent.
+    #[error("input {index} has a malformed time-lock")]
+    MalformedState { index: usize },
+}
+
+impl TimeLockViolation {
+    /// The position of the offending input.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Locked { index, .. } | Self::MalformedState { index } => *index,
+        }
+    }
 }
 ///# [cfg (any (test , feature = "arbitrary-impls"))]
 #[cfg(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"))]
@@ -608,6 +669,63 @@ mod tests {
     );
 }
 #[cfg(test)]
+mod violation_tests {
+    use super::*;
+    use crate::lock_script::LockScript;
+    use crate::native_currency_amount::NativeCurrencyAmount;
+
+    fn utxo(coins: Vec<Coin>) -> Utxo {
+        let mut all_coins = vec![Coin::new_native_currency(NativeCurrencyAmount::coins(1))];
+        all_coins.extend(coins);
+        Utxo::new(LockScript::anyone_can_spend(), all_coins)
+    }
+
+    #[test]
+    fn release_date_must_lie_strictly_before_timestamp() {
+        let now = Timestamp::now();
+        let inputs = [
+            utxo(vec![]),
+            utxo(vec![TimeLock::until(now - Timestamp::days(1))]),
+            utxo(vec![TimeLock::until(now)]),
+            utxo(vec![
+                TimeLock::until(now - Timestamp::days(1)),
+                TimeLock::until(now + Timestamp::days(1)),
+            ]),
+        ];
+
+        assert_eq!(
+            vec![
+                TimeLockViolation::Locked {
+                    index: 2,
+                    release_date: now,
+                },
+                TimeLockViolation::Locked {
+                    index: 3,
+                    release_date: now + Timestamp::days(1),
+                },
+            ],
+            TimeLock::violations(&inputs, now)
+        );
+        assert!(TimeLock::violations(&inputs, now + Timestamp::days(2)).is_empty());
+    }
+
+    #[test]
+    fn malformed_time_lock_is_never_spendable() {
+        let malformed = Coin {
+            type_script_hash: TimeLock.hash(),
+            state: vec![],
+        };
+        let inputs = [utxo(vec![malformed])];
+
+        let violations = TimeLock::violations(&inputs, Timestamp::now());
+        assert_eq!(
+            vec![TimeLockViolation::MalformedState { index: 0 }],
+            violations
+        );
+        assert_eq!(0, violations[0].index());
+    }
+}
+#[cfg(test)]
 #[allow(unused_imports)]
 #[allow(unused_variables)]
 #[allow(unreachable_code)]
@@ -0,0 +1,157 @@
+use crate::transaction_kernel::TransactionKernel;
+use crate::transaction_kernel_id::TransactionKernelId;
+use crate::transaction_proof::TransactionProof;
+use crate::transaction_proof_type::TransactionProofType;
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use twenty_first::prelude::*;
+/// A transaction: the kernel, which holds the transaction's public data, and a
+/// proof that the transaction is valid.
+///
+/// Whether the transaction can be shared with peers, or confirmed into a
+/// block, depends on the [TransactionProofType] of its proof.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub struct Transaction {
+    pub kernel: TransactionKernel,
+    pub proof: TransactionProof,
+}
+
+impl Transaction {
+    pub fn txid(&self) -> TransactionKernelId {
+        self.kernel.txid()
+    }
+
+    pub fn proof_type(&self) -> TransactionProofType {
+        self.proof.proof_type()
+    }
+}
+#[cfg(test)]
+#[allow(unused_imports)]
+#[allow(unused_variables)]
+#[allow(unreachable_code)]
+#[allow(non_snake_case)]
+mod generated_tests {
+    use super::*;
+    use crate::announcement::Announcement;
+    use crate::mutator_set::addition_record::AdditionRecord;
+    use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+    use crate::native_currency_amount::NativeCurrencyAmount;
+    use crate::primitive_witness::PrimitiveWitness;
+    use crate::primitive_witness::SaltedUtxos;
+    use crate::proof_collection::generated_tests::random_proof;
+    use crate::proof_collection::generated_tests::random_proof_collection;
+    use crate::test_shared::*;
+    use crate::timestamp::Timestamp;
+    use crate::transaction_kernel::TransactionKernelProxy;
+    use crate::triton_vm::proof::Proof;
+    pub mod nc {
+        pub use neptune_cash::api::export::AdditionRecord;
+        pub use neptune_cash::api::export::Announcement;
+        pub use neptune_cash::api::export::NativeCurrencyAmount;
+        pub use neptune_cash::api::export::Timestamp;
+        pub use neptune_cash::prelude::twenty_first::prelude::BFieldElement;
+        pub use neptune_cash::protocol::consensus::transaction::Transaction;
+        pub use neptune_cash::protocol::consensus::transaction::primitive_witness::PrimitiveWitness;
+        pub use neptune_cash::protocol::consensus::transaction::primitive_witness::SaltedUtxos;
+        pub use neptune_cash::protocol::consensus::transaction::transaction_kernel::TransactionKernel;
+        pub use neptune_cash::protocol::consensus::transaction::transaction_kernel::TransactionKernelProxy;
+        pub use neptune_cash::protocol::consensus::transaction::transaction_proof::TransactionProof;
+        pub use neptune_cash::util_types::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+    }
+
+    /// A kernel, and the same kernel built with neptune-cash's types.
+    fn kernel() -> (TransactionKernel, nc::TransactionKernel) {
+        let addition_record = rand::random();
+        let timestamp_millis = 1_751_000_000_000;
+        let kernel = TransactionKernelProxy {
+            inputs: vec![],
+            outputs: vec![AdditionRecord::new(addition_record)],
+            announcements: vec![Announcement::new(vec![BFieldElement::new(5)])],
+            fee: NativeCurrencyAmount::coins(1),
+            coinbase: None,
+            timestamp: Timestamp::millis(timestamp_millis),
+            mutator_set_hash: MutatorSetAccumulator::default().hash(),
+            merge_bit: false,
+        }
+        .into_kernel();
+        let nc_kernel = nc::TransactionKernelProxy {
+            inputs: vec![],
+            outputs: vec![nc::AdditionRecord::new(dg(addition_record))],
+            announcements: vec![nc::Announcement::new(vec![nc::BFieldElement::new(5)])],
+            fee: nc::NativeCurrencyAmount::coins(1),
+            coinbase: None,
+            timestamp: nc::Timestamp::millis(timestamp_millis),
+            mutator_set_hash: nc::MutatorSetAccumulator::default().hash(),
+            merge_bit: false,
+        }
+        .into_kernel();
+
+        (kernel, nc_kernel)
+    }
+
+    #[test]
+    fn test_bincode_serialization_for_transaction() {
+        let (kernel, nc_kernel) = kernel();
+        let witness = PrimitiveWitness {
+            input_utxos: SaltedUtxos::empty(),
+            input_membership_proofs: vec![],
+            lock_scripts_and_witnesses: vec![],
+            type_scripts_and_witnesses: vec![],
+            output_utxos: SaltedUtxos::empty(),
+            output_sender_randomnesses: vec![],
+            output_receiver_digests: vec![],
+            mutator_set_accumulator: MutatorSetAccumulator::default(),
+            kernel: kernel.clone(),
+        };
+        let nc_witness = nc::PrimitiveWitness {
+            input_utxos: nc::SaltedUtxos::empty(),
+            input_membership_proofs: vec![],
+            lock_scripts_and_witnesses: vec![],
+            type_scripts_and_witnesses: vec![],
+            output_utxos: nc::SaltedUtxos::empty(),
+            output_sender_randomnesses: vec![],
+            output_receiver_digests: vec![],
+            mutator_set_accumulator: nc::MutatorSetAccumulator::default(),
+            kernel: nc_kernel.clone(),
+        };
+        let (single_proof, nc_single_proof) = random_proof();
+        let (proof_collection, nc_proof_collection) = random_proof_collection();
+        let proofs = [
+            (
+                TransactionProof::Witness(witness),
+                nc::TransactionProof::Witness(nc_witness),
+            ),
+            (
+                TransactionProof::SingleProof(single_proof),
+                nc::TransactionProof::SingleProof(nc_single_proof),
+            ),
+            (
+                TransactionProof::ProofCollection(proof_collection),
+                nc::TransactionProof::ProofCollection(nc_proof_collection),
+            ),
+        ];
+
+        for (proof, nc_proof) in proofs {
+            let original_instance = Transaction {
+                kernel: kernel.clone(),
+                proof,
+            };
+            let nc_instance = nc::Transaction {
+                kernel: nc_kernel.clone(),
+                proof: nc_proof,
+            };
+            test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+        }
+    }
+
+    #[test]
+    fn proof_type_follows_proof() {
+        let transaction = Transaction {
+            kernel: kernel().0,
+            proof: TransactionProof::SingleProof(Proof(vec![])),
+        };
+        assert_eq!(TransactionProofType::SingleProof, transaction.proof_type());
+        assert_eq!(transaction.kernel.txid(), transaction.txid());
+    }
+}
@@ -3,6 +3,8 @@ use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
 use crate::native_currency_amount::NativeCurrencyAmount;
 use crate::network::Network;
 use crate::timestamp::Timestamp;
+use crate::transaction_kernel::TransactionKernel;
+use crate::transaction_kernel::TransactionKernelProxy;
 use crate::tx_input::TxInputList;
 use crate::tx_output::TxOutputList;
 use itertools::Itertools;
@@ -214,43 +216,47 @@ impl TransactionDetails {
                 .validate()
                 .await
         }
+    */
 
-        /// Produce the list of announcements, including the UTXO
-        /// notifications.
-        pub fn announcements(&self) -> Vec<Announcement> {
-            [
-                self.extra_announcements.clone(),
-                self.tx_outputs.announcements(),
-            ]
-            .concat()
-        }
+    /// Produce the list of announcements, including the UTXO
+    /// notifications.
+    pub fn announcements(&self) -> Vec<Announcement> {
+        [
+            self.extra_announcements.clone(),
+            self.tx_outputs.public_announcements(),
+        ]
+        .concat()
+    }
 
+    /* neptune-type todo: needs the programs of the NativeCurrency and
+       TimeLock type scripts, which are not part of this crate. Without them
+       the witness cannot be complete, and no prover accepts it.
 
         pub fn primitive_witness(&self) -> PrimitiveWitness {
             self.into()
         }
+    */
 
-        /// Assemble the transaction kernel corresponding to this
-        /// [`TransactionDetails`] object.
-        pub fn transaction_kernel(&self) -> TransactionKernel {
-            let removal_records = self
-                .tx_inputs
-                .iter()
-                .map(|txi| txi.removal_record(&self.mutator_set_accumulator))
-                .collect_vec();
-            TransactionKernelProxy {
-                inputs: removal_records,
-                outputs: self.tx_outputs.addition_records(),
-                announcements: self.announcements(),
-                fee: self.fee,
-                coinbase: self.coinbase,
-                timestamp: self.timestamp,
-                mutator_set_hash: self.mutator_set_accumulator.hash(),
-                merge_bit: false,
-            }
-            .into_kernel()
+    /// Assemble the transaction kernel corresponding to this
+    /// [`TransactionDetails`] object.
+    pub fn transaction_kernel(&self) -> TransactionKernel {
+        let removal_records = self
+            .tx_inputs
+            .iter()
+            .map(|txi| txi.removal_record(&self.mutator_set_accumulator))
+            .collect_vec();
+        TransactionKernelProxy {
+            inputs: removal_records,
+            outputs: self.tx_outputs.addition_records(),
+            announcements: self.announcements(),
+            fee: self.fee,
+            coinbase: self.coinbase,
+            timestamp: self.timestamp,
+            mutator_set_hash: self.mutator_set_accumulator.hash(),
+            merge_bit: false,
         }
-    */
+        .into_kernel()
+    }
 }
 ///# [cfg (test)]
 #[cfg(all(test, feature = "original-tests"))]
@@ -318,6 +324,91 @@ mod tests {
     }
 }
 #[cfg(test)]
+mod kernel_tests {
+    use twenty_first::prelude::Digest;
+    use twenty_first::prelude::Tip5;
+
+    use super::*;
+    use crate::address::SpendingKey;
+    use crate::block_height::BlockHeight;
+    use crate::mutator_set::commit;
+    use crate::tx_input::TxInput;
+    use crate::tx_output::TxOutput;
+    use crate::unlocked_utxo::UnlockedUtxo;
+    use crate::utxo::Utxo;
+    use crate::wallet_entropy::WalletEntropy;
+
+    fn transaction_details() -> TransactionDetails {
+        let wallet_entropy = WalletEntropy::devnet_wallet();
+        let key = SpendingKey::from(wallet_entropy.nth_generation_spending_key(0));
+        let mutator_set_accumulator = MutatorSetAccumulator::default();
+
+        let utxo = Utxo::new_native_currency(key.lock_script(), NativeCurrencyAmount::coins(10));
+        let membership_proof = mutator_set_accumulator.prove(
+            Tip5::hash(&utxo),
+            Digest::default(),
+            key.privacy_preimage(),
+        );
+        let tx_input = TxInput::from(UnlockedUtxo::unlock(
+            utxo,
+            key.lock_script_and_witness(),
+            membership_proof,
+        ));
+
+        let address = SpendingKey::from(wallet_entropy.nth_symmetric_key(1)).to_address();
+        let tx_output = TxOutput::offchain_native_currency(
+            NativeCurrencyAmount::coins(9),
+            wallet_entropy
+                .generate_sender_randomness(BlockHeight::genesis(), address.privacy_digest()),
+            address,
+            true,
+        );
+
+        TransactionDetails::new_without_coinbase(
+            vec![tx_input],
+            vec![tx_output],
+            NativeCurrencyAmount::coins(1),
+            Timestamp::now(),
+            mutator_set_accumulator,
+            Network::Main,
+        )
+    }
+
+    #[test]
+    fn kernel_matches_transaction_details() {
+        let details = transaction_details();
+        let kernel = details.transaction_kernel();
+
+        assert_eq!(details.fee, kernel.fee);
+        assert_eq!(details.coinbase, kernel.coinbase);
+        assert_eq!(details.timestamp, kernel.timestamp);
+        assert_eq!(details.announcements(), kernel.announcements);
+        assert_eq!(
+            details.mutator_set_accumulator.hash(),
+            kernel.mutator_set_hash
+        );
+        assert!(!kernel.merge_bit);
+
+        let tx_input = &details.tx_inputs[0];
+        assert_eq!(
+            details
+                .mutator_set_accumulator
+                .drop(tx_input.mutator_set_item(), tx_input.mutator_set_mp()),
+            kernel.inputs[0]
+        );
+
+        let tx_output = &details.tx_outputs[0];
+        assert_eq!(
+            commit(
+                Tip5::hash(&tx_output.utxo()),
+                tx_output.sender_randomness(),
+                tx_output.receiver_digest()
+            ),
+            kernel.outputs[0]
+        );
+    }
+}
+#[cfg(test)]
 #[allow(unused_imports)]
 #[allow(unused_variables)]
 #[allow(unreachable_code)]
@@ -1,14 +1,20 @@
 use std::sync::OnceLock;
 
 use get_size2::GetSize;
-// use itertools::Itertools;
+use itertools::Itertools;
+use num_traits::CheckedAdd;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use serde::Deserialize;
 use serde::Serialize;
 use strum::EnumCount;
 use strum::VariantArray;
+use thiserror::Error;
 // use tasm_lib::structure::tasm_object::TasmObject;
 use twenty_first::math::b_field_element::BFieldElement;
 use twenty_first::math::bfield_codec::BFieldCodec;
+use twenty_first::prelude::Tip5;
 use twenty_first::tip5::digest::Digest;
 
 use crate::announcement::Announcement;
@@ -16,11 +22,13 @@ use crate::announcement::Announcement;
 use crate::mast_hash::HasDiscriminant;
 use crate::mast_hash::MastHash;
 use crate::mutator_set::addition_record::AdditionRecord;
+use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+use crate::mutator_set::removal_record::RemovalRecord;
+use crate::mutator_set::removal_record::removal_record_list::RemovalRecordListUnpackError;
 use crate::native_currency_amount::NativeCurrencyAmount;
+use crate::primitive_witness::PrimitiveWitness;
 use crate::timestamp::Timestamp;
-// use crate::util_types::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
-// use crate::util_types::mutator_set::removal_record::removal_record_list::RemovalRecordListUnpackError;
-use crate::mutator_set::removal_record::RemovalRecord;
+use crate::transaction_kernel_id::TransactionKernelId;
 
 /// TransactionKernel is immutable and its hash never changes.
 ///
@@ -98,18 +106,25 @@ impl PartialEq for TransactionKernel {
 
 impl Eq for TransactionKernel {}
 
-/*
 impl From<PrimitiveWitness> for TransactionKernel {
     fn from(transaction_primitive_witness: PrimitiveWitness) -> Self {
         transaction_primitive_witness.kernel
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub(crate) enum TransactionConfirmabilityError {
+/// Reasons why a transaction cannot be confirmed relative to some mutator set.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Error)]
+pub enum TransactionConfirmabilityError {
+    #[error("removal record at input index {0} is invalid")]
     InvalidRemovalRecord(usize),
+
+    #[error("transaction contains duplicate inputs")]
     DuplicateInputs,
+
+    #[error("input at index {0} is already spent")]
     AlreadySpentInput(usize),
+
+    #[error("failed to unpack removal records")]
     RemovalRecordUnpackFailure,
 }
 
@@ -119,10 +134,28 @@ impl From<RemovalRecordListUnpackError> for TransactionConfirmabilityError {
     }
 }
 
+/// Reasons why two transaction kernels cannot be merged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Error)]
+pub enum TransactionMergeError {
+    #[error("cannot merge two coinbase transactions")]
+    TwoCoinbases,
+
+    #[error("transactions are relative to different mutator sets")]
+    MutatorSetMismatch,
+
+    #[error("sum of fees overflows")]
+    FeeOverflow,
+}
+
 impl TransactionKernel {
     /// Check if transaction is confirmable. Inputs must be unpacked before this
     /// check is performed.
-    pub(crate) fn is_confirmable_relative_to(
+    ///
+    /// Packed inputs can be unpacked with
+    /// [`RemovalRecordList::try_unpack`](crate::mutator_set::removal_record::removal_record_list::RemovalRecordList::try_unpack),
+    /// whose error converts into
+    /// [`TransactionConfirmabilityError::RemovalRecordUnpackFailure`].
+    pub fn is_confirmable_relative_to(
         &self,
         mutator_set_accumulator: &MutatorSetAccumulator,
     ) -> Result<(), TransactionConfirmabilityError> {
@@ -157,8 +190,75 @@ impl TransactionKernel {
 
         Ok(())
     }
+
+    /// Produce the kernel of the transaction that results from merging the
+    /// transactions with kernels `self` and `other`.
+    ///
+    /// Inputs, outputs and announcements are concatenated and then shuffled
+    /// with an RNG seeded from `shuffle_seed`, so that the merged kernel does
+    /// not reveal which elements came from which transaction. Fees are added,
+    /// the coinbase (if any) is carried over, and the timestamp is the later
+    /// of the two.
+    ///
+    /// This only computes the kernel. The merged transaction is valid only if
+    /// accompanied by a proof of the merger.
+    pub fn merge_with(
+        &self,
+        other: &TransactionKernel,
+        shuffle_seed: [u8; 32],
+    ) -> Result<TransactionKernel, TransactionMergeError> {
+        if self.mutator_set_hash != other.mutator_set_hash {
+            return Err(TransactionMergeError::MutatorSetMismatch);
+        }
+        if self.coinbase.is_some() && other.coinbase.is_some() {
+            return Err(TransactionMergeError::TwoCoinbases);
+        }
+        let fee = self
+            .fee
+            .checked_add(&other.fee)
+            .ok_or(TransactionMergeError::FeeOverflow)?;
+
+        let mut rng = StdRng::from_seed(shuffle_seed);
+        let mut inputs = [self.inputs.clone(), other.inputs.clone()].concat();
+        inputs.shuffle(&mut rng);
+        let mut outputs = [self.outputs.clone(), other.outputs.clone()].concat();
+        outputs.shuffle(&mut rng);
+        let mut announcements = [self.announcements.clone(), other.announcements.clone()].concat();
+        announcements.shuffle(&mut rng);
+
+        Ok(TransactionKernelProxy {
+            inputs,
+            outputs,
+            announcements,
+            fee,
+            coinbase: self.coinbase.or(other.coinbase),
+            timestamp: self.timestamp.max(other.timestamp),
+            mutator_set_hash: self.mutator_set_hash,
+            merge_bit: true,
+        }
+        .into_kernel())
+    }
+
+    /// Compute the [`TransactionKernelId`] of this kernel.
+    ///
+    /// The ID commits to the MAST hash of the kernel stripped of its inputs
+    /// and mutator set hash, and to the absolute index sets of the inputs.
+    /// Mutator set updates only change the target chunks of the inputs and
+    /// the mutator set hash, so they leave the ID unchanged.
+    pub fn txid(&self) -> TransactionKernelId {
+        let kernel_sans_inputs = TransactionKernelModifier::default()
+            .inputs(vec![])
+            .mutator_set_hash(Digest::default())
+            .clone_modify(self);
+        let index_sets = self
+            .inputs
+            .iter()
+            .map(|removal_record| removal_record.absolute_indices)
+            .collect_vec();
+
+        Tip5::hash(&(kernel_sans_inputs.mast_hash(), index_sets)).into()
+    }
 }
-*/
 
 #[derive(VariantArray, Debug, Clone, EnumCount, Copy, strum::Display)]
 #[strum(serialize_all = "snake_case")]
@@ -304,6 +404,7 @@ pub mod neptune_arbitrary {
         }
     }
 }
+*/
 
 /// performs instantiation and destructuring of [TransactionKernel]
 ///
@@ -313,7 +414,10 @@ pub mod neptune_arbitrary {
 ///
 /// It is also useful for destructuring kernel fields without cloning.
 #[derive(Debug, Clone)]
-#[cfg_attr(any(all(test, feature = "original-tests"), feature = "arbitrary-impls"), derive(arbitrary::Arbitrary))]
+#[cfg_attr(
+    any(all(test, feature = "original-tests"), feature = "arbitrary-impls"),
+    derive(arbitrary::Arbitrary)
+)]
 pub struct TransactionKernelProxy {
     /// contains the transaction inputs.
     pub inputs: Vec<RemovalRecord>,
@@ -465,6 +569,7 @@ impl TransactionKernelModifier {
     }
 }
 
+/*
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub mod tests {
@@ -619,6 +724,221 @@ pub mod tests {
 }
 */
 
+#[cfg(test)]
+mod tests {
+    use twenty_first::prelude::MmrMembershipProof;
+
+    use super::*;
+    use crate::mutator_set::archival_mutator_set::ArchivalMutatorSet;
+    use crate::mutator_set::chunk::Chunk;
+    use crate::mutator_set::commit;
+    use crate::mutator_set::removal_record::absolute_index_set::AbsoluteIndexSet;
+    use crate::mutator_set::removal_record::removal_record_list::RemovalRecordList;
+
+    fn kernel_spending(
+        inputs: Vec<RemovalRecord>,
+        msa: &MutatorSetAccumulator,
+    ) -> TransactionKernel {
+        TransactionKernel {
+            inputs,
+            outputs: vec![],
+            announcements: vec![],
+            fee: NativeCurrencyAmount::coins(0),
+            coinbase: None,
+            timestamp: Timestamp::default(),
+            mutator_set_hash: msa.hash(),
+            merge_bit: false,
+            mast_sequences: Default::default(),
+        }
+    }
+
+    /// Populate a mutator set with a number of items and return removal
+    /// records for the first two.
+    fn mutator_set_with_two_spendable_items() -> (ArchivalMutatorSet, Vec<RemovalRecord>) {
+        let mut archival = ArchivalMutatorSet::default();
+        let mut items = vec![];
+        for _ in 0..10 {
+            let [item, sender_randomness, receiver_preimage]: [Digest; 3] = rand::random();
+            archival.add(&commit(item, sender_randomness, receiver_preimage.hash()));
+            items.push((item, sender_randomness, receiver_preimage));
+        }
+
+        let removal_records = items
+            .into_iter()
+            .take(2)
+            .enumerate()
+            .map(
+                |(aocl_leaf_index, (item, sender_randomness, receiver_preimage))| {
+                    let mp = archival
+                        .restore_membership_proof(
+                            item,
+                            sender_randomness,
+                            receiver_preimage,
+                            aocl_leaf_index as u64,
+                        )
+                        .unwrap();
+                    archival.drop(item, &mp)
+                },
+            )
+            .collect_vec();
+
+        (archival, removal_records)
+    }
+
+    #[test]
+    fn unspent_inputs_are_confirmable() {
+        let (archival, inputs) = mutator_set_with_two_spendable_items();
+        let msa = archival.accumulator();
+        let kernel = kernel_spending(inputs, &msa);
+        assert_eq!(Ok(()), kernel.is_confirmable_relative_to(&msa));
+    }
+
+    #[test]
+    fn can_identify_invalid_removal_records() {
+        let (archival, mut inputs) = mutator_set_with_two_spendable_items();
+        let msa = archival.accumulator();
+
+        // chunk 0 lies in the inactive part of the SWBF, but the removal record
+        // does not carry it
+        inputs.push(RemovalRecord {
+            absolute_indices: AbsoluteIndexSet::new([0; 45]),
+            target_chunks: Default::default(),
+        });
+        let kernel = kernel_spending(inputs, &msa);
+        assert_eq!(
+            Err(TransactionConfirmabilityError::InvalidRemovalRecord(2)),
+            kernel.is_confirmable_relative_to(&msa)
+        );
+    }
+
+    #[test]
+    fn can_identify_double_spends() {
+        let (mut archival, inputs) = mutator_set_with_two_spendable_items();
+        let msa = archival.accumulator();
+
+        let repeated_input = [inputs.clone(), vec![inputs[0].clone()]].concat();
+        let kernel = kernel_spending(repeated_input, &msa);
+        assert_eq!(
+            Err(TransactionConfirmabilityError::DuplicateInputs),
+            kernel.is_confirmable_relative_to(&msa)
+        );
+
+        // spend the second input, then verify that it can no longer be spent
+        archival.remove(&inputs[1]).unwrap();
+        let msa = archival.accumulator();
+        let kernel = kernel_spending(inputs, &msa);
+        assert_eq!(
+            Err(TransactionConfirmabilityError::AlreadySpentInput(1)),
+            kernel.is_confirmable_relative_to(&msa)
+        );
+    }
+
+    #[test]
+    fn unpack_failure_converts_into_confirmability_error() {
+        let (_archival, inputs) = mutator_set_with_two_spendable_items();
+        let mut packed = RemovalRecordList::pack(inputs);
+
+        // no MMR has a tree this tall
+        let illegal_tree_height = 200;
+        packed[0].target_chunks.insert(
+            illegal_tree_height,
+            (MmrMembershipProof::new(vec![]), Chunk::empty_chunk()),
+        );
+        let error = RemovalRecordList::try_unpack(packed).unwrap_err();
+        assert_eq!(
+            TransactionConfirmabilityError::RemovalRecordUnpackFailure,
+            error.into()
+        );
+    }
+
+    #[test]
+    fn merge_combines_kernels() {
+        let (archival, inputs) = mutator_set_with_two_spendable_items();
+        let msa = archival.accumulator();
+        let [output_0, output_1] = [(); 2].map(|_| AdditionRecord::new(rand::random()));
+        let now = Timestamp::now();
+
+        let left = TransactionKernelModifier::default()
+            .outputs(vec![output_0])
+            .announcements(vec![Announcement::new(vec![BFieldElement::new(1)])])
+            .fee(NativeCurrencyAmount::coins(1))
+            .coinbase(Some(NativeCurrencyAmount::coins(5)))
+            .timestamp(now)
+            .modify(kernel_spending(vec![inputs[0].clone()], &msa));
+        let right = TransactionKernelModifier::default()
+            .outputs(vec![output_1])
+            .fee(NativeCurrencyAmount::coins(2))
+            .timestamp(now + Timestamp::minutes(1))
+            .modify(kernel_spending(vec![inputs[1].clone()], &msa));
+
+        let shuffle_seed: [u8; 32] = rand::random();
+        let merged = left.merge_with(&right, shuffle_seed).unwrap();
+        assert_eq!(2, merged.inputs.len());
+        assert!(inputs.iter().all(|input| merged.inputs.contains(input)));
+        assert_eq!(2, merged.outputs.len());
+        assert!(merged.outputs.contains(&output_0));
+        assert!(merged.outputs.contains(&output_1));
+        assert_eq!(left.announcements, merged.announcements);
+        assert_eq!(NativeCurrencyAmount::coins(3), merged.fee);
+        assert_eq!(Some(NativeCurrencyAmount::coins(5)), merged.coinbase);
+        assert_eq!(right.timestamp, merged.timestamp);
+        assert_eq!(msa.hash(), merged.mutator_set_hash);
+        assert!(merged.merge_bit);
+        assert_eq!(merged, left.merge_with(&right, shuffle_seed).unwrap());
+        assert_eq!(Ok(()), merged.is_confirmable_relative_to(&msa));
+    }
+
+    #[test]
+    fn merge_rejects_incompatible_kernels() {
+        let msa = MutatorSetAccumulator::default();
+        let coinbase = TransactionKernelModifier::default()
+            .coinbase(Some(NativeCurrencyAmount::coins(1)))
+            .modify(kernel_spending(vec![], &msa));
+        assert_eq!(
+            Err(TransactionMergeError::TwoCoinbases),
+            coinbase.merge_with(&coinbase, rand::random())
+        );
+
+        let other_mutator_set = TransactionKernelModifier::default()
+            .mutator_set_hash(rand::random())
+            .modify(kernel_spending(vec![], &msa));
+        assert_eq!(
+            Err(TransactionMergeError::MutatorSetMismatch),
+            coinbase.merge_with(&other_mutator_set, rand::random())
+        );
+    }
+
+    #[test]
+    fn txid_is_unaffected_by_mutator_set_updates() {
+        let (mut archival, inputs) = mutator_set_with_two_spendable_items();
+        let kernel = kernel_spending(inputs.clone(), &archival.accumulator());
+
+        // adding items to the mutator set moves the window and updates the
+        // target chunks of the removal records
+        let mut updated_inputs = inputs.clone();
+        for _ in 0..(1 << 12) {
+            let addition_record = AdditionRecord::new(rand::random());
+            RemovalRecord::batch_update_from_addition(
+                &mut updated_inputs.iter_mut().collect_vec(),
+                &archival.accumulator(),
+            );
+            archival.add(&addition_record);
+        }
+        assert_ne!(inputs, updated_inputs);
+
+        let updated_kernel = kernel_spending(updated_inputs, &archival.accumulator());
+        assert_ne!(kernel.mutator_set_hash, updated_kernel.mutator_set_hash);
+        assert_eq!(kernel.txid(), updated_kernel.txid());
+
+        let other_fee = TransactionKernelModifier::default()
+            .fee(NativeCurrencyAmount::coins(1))
+            .clone_modify(&kernel);
+        assert_ne!(kernel.txid(), other_fee.txid());
+
+        let other_inputs = kernel_spending(vec![inputs[0].clone()], &archival.accumulator());
+        assert_ne!(kernel.txid(), other_inputs.txid());
+    }
+}
 
 #[cfg(test)]
 #[allow(unused_imports)]
@@ -637,6 +957,58 @@ mod generated_tests {
         pub use neptune_cash::protocol::consensus::transaction::transaction_kernel::TransactionKernelField;
         pub use neptune_cash::protocol::consensus::transaction::transaction_kernel::TransactionKernelProxy;
         pub use neptune_cash::protocol::consensus::transaction::transaction_kernel::TransactionKernelModifier;
+        pub use neptune_cash::api::export::AbsoluteIndexSet;
+        pub use neptune_cash::api::export::AdditionRecord;
+        pub use neptune_cash::api::export::Announcement;
+        pub use neptune_cash::api::export::ChunkDictionary;
+        pub use neptune_cash::api::export::NativeCurrencyAmount;
+        pub use neptune_cash::api::export::Timestamp;
+        pub use neptune_cash::prelude::twenty_first::prelude::BFieldElement;
+        pub use neptune_cash::util_types::mutator_set::removal_record::RemovalRecord;
+    }
+
+    #[test]
+    fn txid_matches_neptune_cash() {
+        use crate::mutator_set::removal_record::absolute_index_set::AbsoluteIndexSet;
+
+        let addition_record = rand::random();
+        let mutator_set_hash = rand::random();
+        let timestamp_millis = 1_751_000_000_000;
+
+        let kernel = TransactionKernelProxy {
+            inputs: vec![RemovalRecord {
+                absolute_indices: AbsoluteIndexSet::new([7; 45]),
+                target_chunks: Default::default(),
+            }],
+            outputs: vec![AdditionRecord::new(addition_record)],
+            announcements: vec![Announcement::new(vec![BFieldElement::new(3)])],
+            fee: NativeCurrencyAmount::coins(2),
+            coinbase: None,
+            timestamp: Timestamp::millis(timestamp_millis),
+            mutator_set_hash,
+            merge_bit: false,
+        }
+        .into_kernel();
+        let nc_kernel = nc::TransactionKernelProxy {
+            inputs: vec![nc::RemovalRecord {
+                absolute_indices: nc::AbsoluteIndexSet::new([7; 45]),
+                target_chunks: nc::ChunkDictionary::default(),
+            }],
+            outputs: vec![nc::AdditionRecord::new(dg(addition_record))],
+            announcements: vec![nc::Announcement::new(vec![nc::BFieldElement::new(3)])],
+            fee: nc::NativeCurrencyAmount::coins(2),
+            coinbase: None,
+            timestamp: nc::Timestamp::millis(timestamp_millis),
+            mutator_set_hash: dg(mutator_set_hash),
+            merge_bit: false,
+        }
+        .into_kernel();
+
+        assert_eq!(nc_kernel.txid().to_string(), kernel.txid().to_string());
+        assert_eq!(
+            bincode::serialize(&nc_kernel).unwrap(),
+            bincode::serialize(&kernel).unwrap()
+        );
     }
 
     #[test]
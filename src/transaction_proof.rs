@@ -0,0 +1,66 @@
+use crate::primitive_witness::PrimitiveWitness;
+use crate::proof_collection::ProofCollection;
+use crate::transaction_proof_type::TransactionProofType;
+use crate::triton_vm::proof::Proof;
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use twenty_first::prelude::*;
+/// The evidence that a [Transaction](crate::transaction::Transaction) is
+/// valid, in increasing order of proving cost.
+///
+/// See [TransactionProofType] for which variants can be shared and which can
+/// be confirmed into a block.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub enum TransactionProof {
+    /// security: contains secrets and must not be shared.
+    Witness(PrimitiveWitness),
+    SingleProof(Proof),
+    ProofCollection(ProofCollection),
+}
+
+impl TransactionProof {
+    pub fn proof_type(&self) -> TransactionProofType {
+        self.into()
+    }
+
+    /// The single proof, if this is one.
+    pub fn as_single_proof(&self) -> Option<&Proof> {
+        match self {
+            Self::SingleProof(proof) => Some(proof),
+            _ => None,
+        }
+    }
+}
+#[cfg(test)]
+#[allow(unused_imports)]
+#[allow(unused_variables)]
+#[allow(unreachable_code)]
+#[allow(non_snake_case)]
+mod generated_tests {
+    use super::*;
+    use crate::proof_collection::generated_tests::random_proof;
+    use crate::proof_collection::generated_tests::random_proof_collection;
+    use crate::test_shared::*;
+    pub mod nc {
+        pub use neptune_cash::protocol::consensus::transaction::transaction_proof::TransactionProof;
+    }
+    #[test]
+    fn test_bincode_serialization_for_transaction_proof() {
+        let (proof, nc_proof) = random_proof();
+        let (proof_collection, nc_proof_collection) = random_proof_collection();
+        let instances = [
+            (
+                TransactionProof::SingleProof(proof),
+                nc::TransactionProof::SingleProof(nc_proof),
+            ),
+            (
+                TransactionProof::ProofCollection(proof_collection),
+                nc::TransactionProof::ProofCollection(nc_proof_collection),
+            ),
+        ];
+        for (original_instance, nc_instance) in instances {
+            test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::transaction_proof::TransactionProof;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -27,15 +28,15 @@ pub enum TransactionProofType {
     SingleProof = 3,
 }
 
-// impl From<&TransactionProof> for TransactionProofType {
-//     fn from(proof: &TransactionProof) -> Self {
-//         match *proof {
-//             TransactionProof::Witness(_) => Self::PrimitiveWitness,
-//             TransactionProof::ProofCollection(_) => Self::ProofCollection,
-//             TransactionProof::SingleProof(_) => Self::SingleProof,
-//         }
-//     }
-// }
+impl From<&TransactionProof> for TransactionProofType {
+    fn from(proof: &TransactionProof) -> Self {
+        match *proof {
+            TransactionProof::Witness(_) => Self::PrimitiveWitness,
+            TransactionProof::ProofCollection(_) => Self::ProofCollection,
+            TransactionProof::SingleProof(_) => Self::SingleProof,
+        }
+    }
+}
 
 impl TransactionProofType {
     /// indicates if the proof executes in triton-vm.
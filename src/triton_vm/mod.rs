@@ -1,2 +1,3 @@
 pub mod nondeterminism;
+pub mod proof;
 pub mod public_input;
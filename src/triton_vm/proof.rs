@@ -0,0 +1,99 @@
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use twenty_first::prelude::*;
+
+/// The version of the claim format, as used by Triton VM.
+const CURRENT_VERSION: u32 = 0;
+
+/// Contains the public information of a verifiably correct computation.
+///
+/// A corresponding [`Proof`] is needed to verify the computation. One
+/// additional piece of public information not explicitly listed in the
+/// [`Claim`] is the `padded_height`, which is part of the proof.
+#[cfg_attr(
+    any(all(test, feature = "original-tests"), feature = "arbitrary-impls"),
+    derive(arbitrary::Arbitrary)
+)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, GetSize, BFieldCodec)]
+pub struct Claim {
+    /// The hash digest of the program that was executed.
+    pub program_digest: Digest,
+
+    /// The version of the Triton VM instruction set architecture the
+    /// [`program_digest`][digest] is about, as well as of the STARK proof
+    /// system in use.
+    ///
+    /// [digest]: Self::program_digest
+    pub version: u32,
+
+    /// The public input to the computation.
+    pub input: Vec<BFieldElement>,
+
+    /// The public output of the computation.
+    pub output: Vec<BFieldElement>,
+}
+
+impl Claim {
+    /// Create a new Claim.
+    pub fn new(program_digest: Digest) -> Self {
+        Self {
+            program_digest,
+            version: CURRENT_VERSION,
+            input: vec![],
+            output: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn with_input(mut self, input: impl Into<Vec<BFieldElement>>) -> Self {
+        self.input = input.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_output(mut self, output: Vec<BFieldElement>) -> Self {
+        self.output = output;
+        self
+    }
+}
+
+/// A STARK proof, as produced by Triton VM. This crate can carry proofs around
+/// but cannot verify them.
+#[cfg_attr(
+    any(all(test, feature = "original-tests"), feature = "arbitrary-impls"),
+    derive(arbitrary::Arbitrary)
+)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, GetSize, BFieldCodec)]
+pub struct Proof(pub Vec<BFieldElement>);
+
+#[cfg(test)]
+#[allow(unused_imports)]
+#[allow(unused_variables)]
+#[allow(unreachable_code)]
+#[allow(non_snake_case)]
+mod generated_tests {
+    use super::*;
+    use crate::test_shared::*;
+    use twenty_first::bfe_vec;
+
+    pub mod nc {
+        pub use neptune_cash::prelude::triton_vm::prelude::Claim;
+        pub use neptune_cash::prelude::triton_vm::prelude::Proof;
+    }
+
+    #[test]
+    fn test_bincode_serialization_for_claim() {
+        let original_instance = Claim::new(Digest::default()).with_input(bfe_vec![1, 2, 3]);
+        let nc_instance = nc::Claim::new(dg(Digest::default()))
+            .with_input(neptune_cash::prelude::twenty_first::bfe_vec![1, 2, 3]);
+        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+    }
+
+    #[test]
+    fn test_bincode_serialization_for_proof() {
+        let original_instance = Proof(bfe_vec![4, 5, 6]);
+        let nc_instance = nc::Proof(neptune_cash::prelude::twenty_first::bfe_vec![4, 5, 6]);
+        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+    }
+}
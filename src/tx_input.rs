@@ -1,4 +1,6 @@
 //! provides an interface for working with transaction inputs
+use crate::mutator_set::mutator_set_accumulator::MutatorSetAccumulator;
+use crate::mutator_set::removal_record::RemovalRecord;
 use crate::native_currency_amount::NativeCurrencyAmount;
 use crate::unlocked_utxo::UnlockedUtxo;
 use crate::utxo::Utxo;
@@ -35,6 +37,11 @@ impl TxInput {
     pub fn native_currency_amount(&self) -> NativeCurrencyAmount {
         self.utxo.get_native_currency_amount()
     }
+    /// Generate a removal record for this input, relative to the given
+    /// mutator set.
+    pub fn removal_record(&self, mutator_set_accumulator: &MutatorSetAccumulator) -> RemovalRecord {
+        mutator_set_accumulator.drop(self.mutator_set_item(), self.mutator_set_mp())
+    }
 }
 /// Represents a list of [TxInput]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -1,6 +1,8 @@
 //! provides an interface to transaction outputs and associated types
 use crate::address::ReceivingAddress;
 use crate::announcement::Announcement;
+use crate::mutator_set::addition_record::AdditionRecord;
+use crate::mutator_set::commit;
 use crate::native_currency_amount::NativeCurrencyAmount;
 use crate::network::Network;
 use crate::timestamp::Timestamp;
@@ -309,6 +311,15 @@ impl TxOutput {
     pub fn sender_randomness(&self) -> Digest {
         self.sender_randomness
     }
+    /// Compute the addition record that adds this output's UTXO to the
+    /// mutator set.
+    pub fn addition_record(&self) -> AdditionRecord {
+        commit(
+            Tip5::hash(&self.utxo),
+            self.sender_randomness,
+            self.receiver_digest,
+        )
+    }
     pub fn receiver_digest(&self) -> Digest {
         self.receiver_digest
     }
@@ -407,6 +418,10 @@ impl TxOutputList {
     pub fn receiver_digests(&self) -> Vec<Digest> {
         self.iter().map(|x| x.receiver_digest()).collect()
     }
+    /// Returns the addition records of all outputs, in order
+    pub fn addition_records(&self) -> Vec<AdditionRecord> {
+        self.iter().map(|x| x.addition_record()).collect()
+    }
     /// Returns all public announcement for this TxOutputList
     pub fn public_announcements(&self) -> Vec<Announcement> {
         let mut public_announcements = vec![];
@@ -0,0 +1,78 @@
+use crate::triton_vm::nondeterminism::NonDeterminism;
+use get_size2::GetSize;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use triton_isa::program::Program;
+use twenty_first::prelude::*;
+/// A type script together with the nondeterminism it needs to halt
+/// gracefully for a given transaction.
+///
+/// Type scripts define the rules that all coins of a given type obey, for
+/// example [NativeCurrency](crate::native_currency::NativeCurrency) and
+/// [TimeLock](crate::time_lock::TimeLock).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, GetSize, BFieldCodec)]
+pub struct TypeScriptAndWitness {
+    pub program: Program,
+    nd_tokens: Vec<BFieldElement>,
+    nd_memory: Vec<(BFieldElement, BFieldElement)>,
+    nd_digests: Vec<Digest>,
+}
+
+impl TypeScriptAndWitness {
+    pub fn new_with_nondeterminism(program: Program, witness: NonDeterminism) -> Self {
+        Self {
+            program,
+            nd_tokens: witness.individual_tokens,
+            nd_memory: witness.ram.into_iter().collect(),
+            nd_digests: witness.digests,
+        }
+    }
+    pub fn new(program: Program) -> Self {
+        Self {
+            program,
+            nd_tokens: vec![],
+            nd_memory: vec![],
+            nd_digests: vec![],
+        }
+    }
+    pub fn new_with_tokens(program: Program, tokens: Vec<BFieldElement>) -> Self {
+        Self {
+            program,
+            nd_tokens: tokens,
+            nd_memory: vec![],
+            nd_digests: vec![],
+        }
+    }
+    pub fn nondeterminism(&self) -> NonDeterminism {
+        NonDeterminism::new(self.nd_tokens.clone())
+            .with_digests(self.nd_digests.clone())
+            .with_ram(self.nd_memory.iter().copied().collect::<HashMap<_, _>>())
+    }
+}
+#[cfg(test)]
+#[allow(unused_imports)]
+#[allow(unused_variables)]
+#[allow(unreachable_code)]
+#[allow(non_snake_case)]
+mod generated_tests {
+    use super::*;
+    use crate::test_shared::*;
+    use triton_isa::triton_program;
+    pub mod nc {
+        pub use neptune_cash::prelude::triton_vm::prelude::Program;
+        pub use neptune_cash::prelude::twenty_first::prelude::BFieldElement;
+        pub use neptune_cash::protocol::consensus::type_scripts::TypeScriptAndWitness;
+    }
+    #[test]
+    fn test_bincode_serialization_for_type_script_and_witness() {
+        let program = triton_program! { push 1 assert halt };
+        let original_instance = TypeScriptAndWitness::new_with_tokens(program, bfe_vec![4, 5, 6]);
+        let nc_program = nc::Program::from_code("push 1 assert halt").unwrap();
+        let nc_instance = nc::TypeScriptAndWitness::new_with_tokens(
+            nc_program,
+            [4, 5, 6].map(nc::BFieldElement::new).to_vec(),
+        );
+        test_bincode_serialization_for_type(original_instance, Some(nc_instance));
+    }
+}
@@ -139,6 +139,14 @@ impl Utxo {
     pub fn release_date(&self) -> Option<Timestamp> {
         self.coins.iter().find_map(Coin::release_date)
     }
+    /// Determine whether the UTXO's time-locks, if any, allow spending it in a
+    /// transaction with the given timestamp.
+    ///
+    /// Agrees with [TimeLock::violations]: every time-lock must have expired,
+    /// and a malformed time-lock can never be spent.
+    pub fn can_spend_at(&self, timestamp: Timestamp) -> bool {
+        TimeLock::violations(std::slice::from_ref(self), timestamp).is_empty()
+    }
     /// Test the coins for state validity, relative to known type scripts.
     /// Adds a time-lock coin, if necessary.
     ///